@@ -0,0 +1,8 @@
+//! This module contains tools for the acoustics of pitches, like the harmonic series and the
+//! roughness of chords
+
+mod harmonics;
+mod roughness;
+
+pub use harmonics::{Partial, fundamentals, partials};
+pub use roughness::{Spectrum, plomp_levelt};
@@ -0,0 +1,166 @@
+use crate::{
+    div_remainder,
+    harmony::{Interval, Pitch},
+    tuning::Tuning,
+};
+
+/// the diatonic steps used to spell each chromatic step above a fundamental, so that the
+/// seventh partial is a minor seventh, the eleventh an augmented fourth and the thirteenth a
+/// minor sixth
+const DIATONIC_STEPS: [i16; 12] = [0, 1, 1, 2, 2, 3, 3, 4, 5, 5, 6, 6];
+
+/// the interval spanning `chromatic` half steps, spelled as in the harmonic series
+fn spell_interval(chromatic: i16) -> Interval {
+    let (octaves, step) = div_remainder(chromatic, 12);
+    #[allow(clippy::cast_sign_loss)]
+    Interval::new(chromatic, 7 * octaves + DIATONIC_STEPS[step as usize])
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A partial of a harmonic series, together with the nearest spelled pitch
+///
+/// Returned by [`partials`] for the partials above a fundamental, and by [`fundamentals`] for
+/// the fundamentals below a partial.
+pub struct Partial {
+    number: u32,
+    frequency: f64,
+    pitch: Pitch,
+    cents: f64,
+}
+
+impl Partial {
+    /// returns the number of the partial, the fundamental is partial 1
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+
+    /// returns the exact frequency in Hz
+    pub fn frequency(&self) -> f64 {
+        self.frequency
+    }
+
+    /// returns the nearest pitch
+    pub fn pitch(&self) -> Pitch {
+        self.pitch
+    }
+
+    /// returns the deviation of the frequency from the nearest pitch in cents
+    pub fn cents(&self) -> f64 {
+        self.cents
+    }
+}
+
+/// Returns the first `count` partials of the harmonic series above `fundamental`.
+///
+/// The nearest pitch of each partial is found with [`Tuning::nearest_chromatic`] and spelled
+/// relative to the fundamental, so the seventh partial is a minor seventh, the eleventh an
+/// augmented fourth and the thirteenth a minor sixth above the fundamental in some octave.
+///
+/// # Examples
+/// ```
+/// # use music_types::{acoustics::partials, harmony::{Pitch, ParsePitchError}, tuning::EqualTemperament};
+/// # use std::str::FromStr;
+/// let series = partials(Pitch::from_str("C2")?, 8, &EqualTemperament::default());
+/// assert_eq!(series[4].number(), 5);
+/// assert_eq!(series[4].pitch(), Pitch::from_str("E4")?);
+/// assert!((series[4].cents() + 13.69).abs() < 0.01);
+/// assert_eq!(series[6].pitch(), Pitch::from_str("Bb4")?);
+/// assert!((series[6].cents() + 31.17).abs() < 0.01);
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub fn partials(fundamental: Pitch, count: u32, tuning: &(impl Tuning + ?Sized)) -> Vec<Partial> {
+    let base = tuning.frequency(fundamental);
+    (1..=count)
+        .map(|number| {
+            let frequency = base * f64::from(number);
+            let steps = tuning.nearest_chromatic(frequency).0.to_num() - fundamental.chromatic;
+            let pitch = fundamental + spell_interval(steps);
+            Partial {
+                number,
+                frequency,
+                pitch,
+                cents: 1200.0 * (frequency / tuning.frequency(pitch)).log2(),
+            }
+        })
+        .collect()
+}
+
+/// Returns the fundamentals which have `pitch` as one of their first `count` partials.
+///
+/// The partial at index `i` describes the fundamental of which `pitch` is partial `i + 1`,
+/// with its exact frequency and the nearest pitch spelled as in [`partials`].
+///
+/// # Examples
+/// ```
+/// # use music_types::{acoustics::fundamentals, harmony::{Pitch, ParsePitchError}, tuning::EqualTemperament};
+/// # use std::str::FromStr;
+/// let below = fundamentals(Pitch::from_str("F#5")?, 11, &EqualTemperament::default());
+/// assert_eq!(below[2].pitch(), Pitch::from_str("B3")?);
+/// assert_eq!(below[10].pitch(), Pitch::from_str("C2")?);
+/// assert!((below[10].cents() - 48.68).abs() < 0.01);
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub fn fundamentals(pitch: Pitch, count: u32, tuning: &(impl Tuning + ?Sized)) -> Vec<Partial> {
+    let base = tuning.frequency(pitch);
+    (1..=count)
+        .map(|number| {
+            let frequency = base / f64::from(number);
+            let steps = pitch.chromatic - tuning.nearest_chromatic(frequency).0.to_num();
+            let fundamental = pitch + -spell_interval(steps);
+            Partial {
+                number,
+                frequency,
+                pitch: fundamental,
+                cents: 1200.0 * (frequency / tuning.frequency(fundamental)).log2(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::tuning::{EqualTemperament, JustIntonation};
+
+    #[test]
+    fn spelling() {
+        let names: Vec<_> = partials(
+            Pitch::from_str("C2").unwrap(),
+            19,
+            &EqualTemperament::default(),
+        )
+        .iter()
+        .map(|p| p.pitch().to_string())
+        .collect();
+        assert_eq!(
+            names,
+            [
+                "C2", "C3", "G3", "C4", "E4", "G4", "Bb4", "C5", "D5", "E5", "F#5", "G5", "Ab5",
+                "Bb5", "B5", "C6", "Db6", "D6", "Eb6"
+            ]
+        );
+    }
+
+    #[test]
+    fn inverse() {
+        let tuning = EqualTemperament::default();
+        let fundamental = Pitch::from_str("Eb1").unwrap();
+        for partial in partials(fundamental, 16, &tuning) {
+            let below = fundamentals(partial.pitch(), 16, &tuning);
+            let found = below[partial.number() as usize - 1];
+            assert_eq!(found.pitch(), fundamental);
+            assert!((found.cents() + partial.cents()).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn just() {
+        let tuning = JustIntonation::new(Pitch::from_str("C4").unwrap(), 261.63);
+        for partial in partials(Pitch::from_str("C3").unwrap(), 6, &tuning) {
+            assert!(partial.cents().abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{harmony::Pitch, tuning::Tuning};
+
+/// Returns the roughness of two sine tones after Plomp and Levelt, as parametrized by Sethares.
+///
+/// The roughness is scaled by the smaller amplitude. It vanishes for equal frequencies, peaks
+/// at about a quarter of the critical bandwidth and decays for wider intervals.
+///
+/// # Examples
+/// ```
+/// # use music_types::acoustics::plomp_levelt;
+/// assert_eq!(plomp_levelt(440.0, 1.0, 440.0, 1.0), 0.0);
+/// let semitone = plomp_levelt(440.0, 1.0, 466.16, 1.0);
+/// let fifth = plomp_levelt(440.0, 1.0, 659.26, 1.0);
+/// assert!(semitone > fifth);
+/// ```
+pub fn plomp_levelt(frequency_1: f64, amplitude_1: f64, frequency_2: f64, amplitude_2: f64) -> f64 {
+    // constants from Sethares, "Tuning, Timbre, Spectrum, Scale", appendix E
+    const X_STAR: f64 = 0.24;
+    const S_1: f64 = 0.0207;
+    const S_2: f64 = 18.96;
+    const B_1: f64 = 3.51;
+    const B_2: f64 = 5.75;
+
+    let (low, high) = if frequency_1 <= frequency_2 {
+        (frequency_1, frequency_2)
+    } else {
+        (frequency_2, frequency_1)
+    };
+    let s = X_STAR / S_1.mul_add(low, S_2);
+    let difference = high - low;
+    amplitude_1.min(amplitude_2) * ((-B_1 * s * difference).exp() - (-B_2 * s * difference).exp())
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The spectrum of a tone, given as partials with their frequency relative to the fundamental
+/// and their amplitude
+///
+/// # Examples
+/// ```
+/// # use music_types::{acoustics::Spectrum, harmony::{Pitch, ParsePitchError}, tuning::EqualTemperament};
+/// # use std::str::FromStr;
+/// let spectrum = Spectrum::harmonic(6, 0.88);
+/// let tuning = EqualTemperament::default();
+/// let chord = |names: &[&str]| -> Result<Vec<Pitch>, ParsePitchError> {
+///     names.iter().map(|s| Pitch::from_str(s)).collect()
+/// };
+/// let major = spectrum.roughness(&chord(&["C4", "E4", "G4"])?, &tuning);
+/// let cluster = spectrum.roughness(&chord(&["C4", "Db4", "D4"])?, &tuning);
+/// assert!(major < cluster);
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub struct Spectrum {
+    partials: Vec<(f64, f64)>,
+}
+
+impl Spectrum {
+    /// creates a spectrum from pairs of frequency ratio and amplitude
+    pub fn new(partials: Vec<(f64, f64)>) -> Self {
+        Self { partials }
+    }
+
+    /// creates the spectrum of a pure sine tone
+    pub fn sine() -> Self {
+        Self::new(vec![(1.0, 1.0)])
+    }
+
+    /// creates a harmonic spectrum with `count` partials, where the amplitude of each partial
+    /// is `decay` times the amplitude of the one below
+    pub fn harmonic(count: u32, decay: f64) -> Self {
+        Self::new(
+            (1..=count)
+                .map(|n| (f64::from(n), decay.powf(f64::from(n - 1))))
+                .collect(),
+        )
+    }
+
+    /// returns the partials as pairs of frequency ratio and amplitude
+    pub fn partials(&self) -> &[(f64, f64)] {
+        &self.partials
+    }
+
+    /// Returns the roughness of tones with this spectrum at the given fundamental frequencies.
+    ///
+    /// The roughness is the sum of [`plomp_levelt`] over all pairs of partials of all tones.
+    pub fn frequency_roughness(&self, frequencies: &[f64]) -> f64 {
+        let partials: Vec<_> = frequencies
+            .iter()
+            .flat_map(|f| self.partials.iter().map(move |(r, a)| (f * r, *a)))
+            .collect();
+        partials
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| partials[i + 1..].iter().map(move |b| (a, b)))
+            .map(|((f_1, a_1), (f_2, a_2))| plomp_levelt(*f_1, *a_1, *f_2, *a_2))
+            .sum()
+    }
+
+    /// Returns the roughness of the pitches with this spectrum in the given tuning.
+    ///
+    /// See [`frequency_roughness`][`Self::frequency_roughness`].
+    pub fn roughness(&self, pitches: &[Pitch], tuning: &(impl Tuning + ?Sized)) -> f64 {
+        let frequencies: Vec<_> = pitches.iter().map(|p| tuning.frequency(*p)).collect();
+        self.frequency_roughness(&frequencies)
+    }
+}
+
+impl Default for Spectrum {
+    fn default() -> Self {
+        Self::harmonic(6, 0.88)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::tuning::{EqualTemperament, JustIntonation};
+
+    #[test]
+    fn sine_curve() {
+        // the roughness of two sines peaks below a minor third and is symmetric
+        let curve: Vec<_> = (0..=48)
+            .map(|step| plomp_levelt(440.0, 1.0, 440.0 * (f64::from(step) / 48.0).exp2(), 1.0))
+            .collect();
+        let peak = (0..curve.len())
+            .max_by(|a, b| curve[*a].total_cmp(&curve[*b]))
+            .unwrap();
+        assert!((1..=8).contains(&peak));
+        assert!(curve[48] < curve[peak] / 10.0);
+        let difference =
+            plomp_levelt(500.0, 1.0, 600.0, 0.5) - plomp_levelt(600.0, 0.5, 500.0, 1.0);
+        assert!(difference.abs() < 1e-12);
+    }
+
+    #[test]
+    fn intervals() {
+        let spectrum = Spectrum::default();
+        let tuning = EqualTemperament::default();
+        let c = Pitch::from_str("C4").unwrap();
+        let dyad = |s: &str| spectrum.roughness(&[c, Pitch::from_str(s).unwrap()], &tuning);
+        assert!(dyad("C5") < dyad("G4"));
+        assert!(dyad("G4") < dyad("E4"));
+        assert!(dyad("E4") < dyad("C#4"));
+        assert!(dyad("F#4") > dyad("F4"));
+    }
+
+    #[test]
+    fn just_fifth() {
+        let spectrum = Spectrum::default();
+        let c = Pitch::from_str("C4").unwrap();
+        let g = Pitch::from_str("G4").unwrap();
+        let just = spectrum.roughness(&[c, g], &JustIntonation::new(c, 261.63));
+        let equal = spectrum.roughness(&[c, g], &EqualTemperament::default());
+        assert!(just < equal);
+    }
+}
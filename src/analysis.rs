@@ -0,0 +1,9 @@
+//! This module contains tools for analysing melodies and harmonic progressions
+
+mod key_estimation;
+mod modulation;
+mod non_chord_tones;
+
+pub use key_estimation::{KeyEstimate, KeyProfile};
+pub use modulation::{PivotChord, modulation_paths, pivot_chords};
+pub use non_chord_tones::{ToneClass, classify_tones};
@@ -0,0 +1,325 @@
+use crate::{
+    harmony::{
+        Accidental, ChromaticOctave, Pitch, PitchName,
+        scale::{Key, KeySignature},
+    },
+    rhythm::Timed,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The key profile used in the Krumhansl-Schmuckler key finding algorithm
+///
+/// A key profile assigns a weight to each chromatic scale degree of a major and a minor key.
+/// The pitch class distribution of a passage is correlated with the profile rotated to each of
+/// the 24 major and minor keys.
+///
+/// # Examples
+/// ```
+/// # use music_types::{analysis::KeyProfile, harmony::{scale::Key, Pitch, ParsePitchError}, rhythm::Timed};
+/// # use std::str::FromStr;
+/// let notes: Vec<_> = ["G4", "B4", "D5", "F#5", "G5", "A4", "C5", "D4"]
+///     .iter()
+///     .zip(0..)
+///     .map(|(s, i)| Ok(Timed::new(Pitch::from_str(s)?, f64::from(i), 1.0)))
+///     .collect::<Result<_, ParsePitchError>>()?;
+///
+/// let best = &KeyProfile::KrumhanslKessler.estimate(&notes)[0];
+/// assert_eq!(best.key(), &Key::major(Pitch::class_from_str("G")?));
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub enum KeyProfile {
+    /// The probe tone profiles from Krumhansl and Kessler (1982)
+    #[default]
+    KrumhanslKessler,
+    /// The profiles derived by Temperley (2007) from the Kostka-Payne corpus
+    Temperley,
+    /// The profiles derived by Aarden (2003) from the Essen folksong collection
+    Aarden,
+}
+
+impl KeyProfile {
+    /// returns the weights for the major and the minor key starting at the tonic
+    pub fn weights(&self) -> ([f64; 12], [f64; 12]) {
+        match self {
+            Self::KrumhanslKessler => (
+                [
+                    6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+                ],
+                [
+                    6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
+                ],
+            ),
+            Self::Temperley => (
+                [
+                    0.748, 0.060, 0.488, 0.082, 0.670, 0.460, 0.096, 0.715, 0.104, 0.366, 0.057,
+                    0.400,
+                ],
+                [
+                    0.712, 0.084, 0.474, 0.618, 0.049, 0.460, 0.105, 0.747, 0.404, 0.067, 0.133,
+                    0.330,
+                ],
+            ),
+            Self::Aarden => (
+                [
+                    17.7661, 0.145_624, 14.9265, 0.160_186, 19.8049, 11.3587, 0.291_248, 22.062,
+                    0.145_624, 8.15494, 0.232_998, 4.95122,
+                ],
+                [
+                    18.2648, 0.737_619, 14.0499, 16.8599, 0.702_494, 14.4362, 0.702_494, 18.6161,
+                    4.56621, 1.93186, 7.37619, 1.75623,
+                ],
+            ),
+        }
+    }
+
+    /// Estimates the key of `notes` and returns all 24 major and minor keys ranked from the
+    /// most to the least likely.
+    ///
+    /// Each note is weighted by its duration.
+    /// The spelling of each tonic is chosen to keep the key signature small,
+    /// ties are broken by the spelling used in `notes`.
+    pub fn estimate(&self, notes: &[Timed<Pitch>]) -> Vec<KeyEstimate> {
+        let mut profile = Distribution::default();
+        for note in notes {
+            profile.add(note.value, note.duration);
+        }
+        self.rank(&profile)
+    }
+
+    /// Estimates the key in windows of length `window` which start every `hop` beats.
+    ///
+    /// Returns the most likely key for each window, placed at the time of the window.
+    /// Notes are weighted by the duration for which they overlap with the window.
+    /// Windows in which no note sounds are skipped.
+    ///
+    /// # Panics
+    /// Panics if `hop` is not positive.
+    pub fn estimate_windowed(
+        &self,
+        notes: &[Timed<Pitch>],
+        window: f64,
+        hop: f64,
+    ) -> Vec<Timed<KeyEstimate>> {
+        assert!(hop > 0.0, "the hop between windows must be positive");
+        let Some(start) = notes.iter().map(Timed::onset).reduce(f64::min) else {
+            return Vec::new();
+        };
+        let end = notes.iter().map(Timed::end).fold(start, f64::max);
+
+        let mut estimates = Vec::new();
+        let mut onset = start;
+        while onset < end {
+            let mut profile = Distribution::default();
+            for note in notes {
+                let overlap = note.end().min(onset + window) - note.onset.max(onset);
+                if overlap > 0.0 {
+                    profile.add(note.value, overlap);
+                }
+            }
+            if profile.total() > 0.0 {
+                let best = self.rank(&profile).swap_remove(0);
+                estimates.push(Timed::new(best, onset, window));
+            }
+            onset += hop;
+        }
+        estimates
+    }
+
+    fn rank(&self, profile: &Distribution) -> Vec<KeyEstimate> {
+        let (major, minor) = self.weights();
+        let mut estimates = Vec::with_capacity(24);
+        for tonic in 0..12 {
+            estimates.push(KeyEstimate {
+                score: correlation(&profile.weights, &major, tonic),
+                key: Key::major(profile.spell_tonic(tonic, Key::major)),
+            });
+            estimates.push(KeyEstimate {
+                score: correlation(&profile.weights, &minor, tonic),
+                key: Key::minor(profile.spell_tonic(tonic, Key::minor)),
+            });
+        }
+        estimates.sort_by(|a, b| b.score.total_cmp(&a.score));
+        estimates
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A candidate key together with its correlation to the analysed passage
+pub struct KeyEstimate {
+    key: Key,
+    score: f64,
+}
+
+impl KeyEstimate {
+    /// returns the estimated key
+    pub fn key(&self) -> &Key {
+        &self.key
+    }
+
+    /// returns the correlation between the passage and the key profile, in -1.0..=1.0
+    pub fn score(&self) -> f64 {
+        self.score
+    }
+
+    /// returns the key signature of the estimated key
+    pub fn key_signature(&self) -> KeySignature {
+        self.key.key_signature()
+    }
+}
+
+/// A pitch class distribution which also remembers the spellings it has seen
+#[derive(Default)]
+struct Distribution {
+    weights: [f64; 12],
+    spellings: Vec<(Pitch, f64)>,
+}
+
+impl Distribution {
+    fn add(&mut self, pitch: Pitch, weight: f64) {
+        let class = pitch_class(pitch);
+        self.weights[class] += weight;
+        let pitch = Pitch::from_pitch_class(pitch.pitch_name(), pitch.accidental());
+        match self.spellings.iter_mut().find(|(p, _)| *p == pitch) {
+            Some((_, w)) => *w += weight,
+            None => self.spellings.push((pitch, weight)),
+        }
+    }
+
+    fn total(&self) -> f64 {
+        self.weights.iter().sum()
+    }
+
+    fn spelling_weight(&self, pitch: Pitch) -> f64 {
+        self.spellings
+            .iter()
+            .find(|(p, _)| *p == pitch)
+            .map_or(0.0, |(_, w)| *w)
+    }
+
+    /// chooses the spelling with the smallest key signature,
+    /// ties are broken by the weight of the spelling in the distribution
+    fn spell_tonic(&self, class: usize, key: fn(Pitch) -> Key) -> Pitch {
+        let names = [
+            PitchName::C,
+            PitchName::D,
+            PitchName::E,
+            PitchName::F,
+            PitchName::G,
+            PitchName::A,
+            PitchName::B,
+        ];
+        let accidentals = [Accidental::FLAT, Accidental::NATURAL, Accidental::SHARP];
+        names
+            .into_iter()
+            .flat_map(|name| accidentals.map(|acc| Pitch::from_pitch_class(name, acc)))
+            .filter(|p| pitch_class(*p) == class)
+            .min_by(|a, b| {
+                let fifths = |p: &Pitch| key(*p).key_signature().fifths().abs();
+                fifths(a).cmp(&fifths(b)).then(
+                    self.spelling_weight(*b)
+                        .total_cmp(&self.spelling_weight(*a)),
+                )
+            })
+            .expect("every pitch class has a spelling with at most one accidental")
+    }
+}
+
+fn pitch_class(pitch: Pitch) -> usize {
+    usize::try_from((pitch.to_chromatic() % ChromaticOctave).to_num())
+        .expect("the remainder is not negative")
+}
+
+/// the pearson correlation between `values` and `profile` rotated to start at `tonic`
+fn correlation(values: &[f64; 12], profile: &[f64; 12], tonic: usize) -> f64 {
+    let mean = |xs: &[f64; 12]| xs.iter().sum::<f64>() / 12.0;
+    let (mean_v, mean_p) = (mean(values), mean(profile));
+    let (mut cov, mut var_v, mut var_p) = (0.0, 0.0, 0.0);
+    for (i, value) in values.iter().enumerate() {
+        let v = value - mean_v;
+        let p = profile[(i + 12 - tonic) % 12] - mean_p;
+        cov += v * p;
+        var_v += v * v;
+        var_p += p * p;
+    }
+    if var_v == 0.0 {
+        return 0.0;
+    }
+    cov / (var_v * var_p).sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn notes(pitches: &[&str]) -> Vec<Timed<Pitch>> {
+        pitches
+            .iter()
+            .zip(0..)
+            .map(|(s, i)| Timed::new(Pitch::from_str(s).unwrap(), f64::from(i), 1.0))
+            .collect()
+    }
+
+    #[test]
+    fn scales() {
+        let profiles = [
+            KeyProfile::KrumhanslKessler,
+            KeyProfile::Temperley,
+            KeyProfile::Aarden,
+        ];
+        let c_major = notes(&["C4", "D4", "E4", "F4", "G4", "A4", "B4", "C5", "G4", "C4"]);
+        let a_minor = notes(&["A3", "B3", "C4", "D4", "E4", "F4", "G#4", "A4", "E4", "A3"]);
+        for profile in profiles {
+            let best = &profile.estimate(&c_major)[0];
+            assert_eq!(best.key(), &Key::major(Pitch::class_from_str("C").unwrap()));
+            let best = &profile.estimate(&a_minor)[0];
+            assert_eq!(best.key(), &Key::minor(Pitch::class_from_str("A").unwrap()));
+        }
+    }
+
+    #[test]
+    fn spelling() {
+        let estimates = KeyProfile::default().estimate(&notes(&["Db4", "F4", "Ab4", "Db5"]));
+        assert_eq!(
+            estimates[0].key(),
+            &Key::major(Pitch::class_from_str("Db").unwrap())
+        );
+        // C# major would need seven sharps
+        let estimates = KeyProfile::default().estimate(&notes(&["C#4", "F4", "G#4", "C#5"]));
+        assert_eq!(
+            estimates[0].key(),
+            &Key::major(Pitch::class_from_str("Db").unwrap())
+        );
+        // F# and Gb major both need six accidentals
+        let estimates = KeyProfile::default().estimate(&notes(&["F#4", "A#4", "C#5", "F#5"]));
+        assert_eq!(
+            estimates[0].key(),
+            &Key::major(Pitch::class_from_str("F#").unwrap())
+        );
+    }
+
+    #[test]
+    fn modulation() {
+        let mut passage = notes(&["C4", "E4", "G4", "F4", "D4", "B3", "C4", "G4"]);
+        passage.extend(
+            notes(&["D4", "F#4", "A4", "G4", "E4", "C#4", "D4", "A4"])
+                .into_iter()
+                .map(|n| Timed::new(n.value, n.onset + 8.0, n.duration)),
+        );
+        let keys: Vec<_> = KeyProfile::default()
+            .estimate_windowed(&passage, 8.0, 8.0)
+            .into_iter()
+            .map(|e| e.into_value().key)
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                Key::major(Pitch::class_from_str("C").unwrap()),
+                Key::major(Pitch::class_from_str("D").unwrap()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::VecDeque;
+
+use crate::harmony::{
+    ChromaticOctave, Octave,
+    chord::{Chord, RomanNumeral, TriadQuality},
+    scale::Key,
+};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A chord which is diatonic to two keys, together with its function in each key
+pub struct PivotChord {
+    chord: Chord,
+    from: RomanNumeral,
+    to: RomanNumeral,
+}
+
+impl PivotChord {
+    /// returns the chord, with its root as a pitch class
+    pub fn chord(&self) -> &Chord {
+        &self.chord
+    }
+
+    /// returns the roman numeral of the chord in the source key
+    pub fn from(&self) -> RomanNumeral {
+        self.from
+    }
+
+    /// returns the roman numeral of the chord in the target key
+    pub fn to(&self) -> RomanNumeral {
+        self.to
+    }
+}
+
+/// Lists the triads which are diatonic to both keys.
+///
+/// The chords are compared by spelling and listed in the order of the scale degrees of `from`.
+///
+/// # Examples
+/// ```
+/// # use music_types::{analysis::pivot_chords, harmony::{scale::Key, Pitch, ParsePitchError}};
+/// let c_major = Key::major(Pitch::class_from_str("C")?);
+/// let g_major = Key::major(Pitch::class_from_str("G")?);
+/// let pivots: Vec<_> = pivot_chords(&c_major, &g_major)
+///     .iter()
+///     .map(|p| format!("{} = {}", p.from(), p.to()))
+///     .collect();
+/// assert_eq!(pivots, vec!["I = IV", "iii = vi", "V = I", "vi = ii"]);
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub fn pivot_chords(from: &Key, to: &Key) -> Vec<PivotChord> {
+    let to_triads: Vec<_> = to
+        .triads()
+        .into_iter()
+        .map(|c| pitch_class_root(&c))
+        .collect();
+    from.triads()
+        .into_iter()
+        .map(|c| pitch_class_root(&c))
+        .filter(|chord| to_triads.contains(chord))
+        .filter_map(|chord| {
+            Some(PivotChord {
+                from: from.roman_numeral(&chord)?,
+                to: to.roman_numeral(&chord)?,
+                chord,
+            })
+        })
+        .collect()
+}
+
+/// Finds the shortest modulation paths from one key to another.
+///
+/// Each step of a path moves to a closely related key, that is a major or minor key whose tonic
+/// triad is diatonic to the current key and whose key signature differs by at most one
+/// accidental. The first key of every path is `from` and the last is `to`.
+/// Keys are reached if their tonic has the same chromatic pitch class as the tonic of `to` and
+/// they have the same scale, so enharmonic targets are found as well.
+///
+/// Returns an empty vector if `to` cannot be reached within twelve steps.
+///
+/// # Examples
+/// ```
+/// # use music_types::{analysis::modulation_paths, harmony::{scale::Key, Pitch, ParsePitchError}};
+/// let c_major = Key::major(Pitch::class_from_str("C")?);
+/// let a_major = Key::major(Pitch::class_from_str("A")?);
+/// let paths = modulation_paths(&c_major, &a_major);
+/// // every path passes through two intermediate keys
+/// assert!(paths.iter().all(|p| p.len() == 4));
+/// assert!(paths.contains(&vec![
+///     c_major.clone(),
+///     Key::major(Pitch::class_from_str("G")?),
+///     Key::major(Pitch::class_from_str("D")?),
+///     a_major.clone(),
+/// ]));
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub fn modulation_paths(from: &Key, to: &Key) -> Vec<Vec<Key>> {
+    const MAX_STEPS: usize = 12;
+
+    let reached = |key: &Key| {
+        key.scale() == to.scale()
+            && key.tonic().to_chromatic() % ChromaticOctave
+                == to.tonic().to_chromatic() % ChromaticOctave
+    };
+    if reached(from) {
+        return vec![vec![from.clone()]];
+    }
+
+    // breadth first search remembering every predecessor on a shortest path
+    let mut keys = vec![from.clone()];
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new()];
+    let mut depth = vec![0];
+    let mut queue = VecDeque::from([0]);
+    let mut targets = Vec::new();
+    while let Some(current) = queue.pop_front() {
+        if depth[current] >= MAX_STEPS
+            || (!targets.is_empty() && depth[current] >= depth[targets[0]])
+        {
+            break;
+        }
+        for neighbor in closely_related(&keys[current]) {
+            match keys.iter().position(|k| *k == neighbor) {
+                Some(index) if depth[index] == depth[current] + 1 => {
+                    predecessors[index].push(current);
+                }
+                Some(_) => (),
+                None => {
+                    let index = keys.len();
+                    if reached(&neighbor) {
+                        targets.push(index);
+                    }
+                    keys.push(neighbor);
+                    predecessors.push(vec![current]);
+                    depth.push(depth[current] + 1);
+                    queue.push_back(index);
+                }
+            }
+        }
+    }
+
+    let mut paths = Vec::new();
+    for target in targets {
+        let mut partial = vec![vec![target]];
+        while let Some(path) = partial.pop() {
+            let Some(&last) = path.last() else { continue };
+            if predecessors[last].is_empty() {
+                let mut path: Vec<_> = path.iter().rev().map(|&i| keys[i].clone()).collect();
+                if let Some(target) = path.last_mut() {
+                    *target = to.clone();
+                }
+                paths.push(path);
+                continue;
+            }
+            for &prev in &predecessors[last] {
+                let mut path = path.clone();
+                path.push(prev);
+                partial.push(path);
+            }
+        }
+    }
+    paths
+}
+
+fn closely_related(key: &Key) -> Vec<Key> {
+    let signature = key.key_signature();
+    key.triads()
+        .into_iter()
+        .filter_map(|chord| match chord.triad_quality()? {
+            TriadQuality::Major => Some(Key::major(chord.root())),
+            TriadQuality::Minor => Some(Key::minor(chord.root())),
+            TriadQuality::Diminished | TriadQuality::Augmented => None,
+        })
+        .filter(|k| k != key && k.key_signature().distance(&signature) <= 1)
+        .collect()
+}
+
+fn pitch_class_root(chord: &Chord) -> Chord {
+    let root = chord.root();
+    chord.transpose((root % Octave) - root)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::harmony::Pitch;
+
+    fn major(s: &str) -> Key {
+        Key::major(Pitch::class_from_str(s).unwrap())
+    }
+
+    fn minor(s: &str) -> Key {
+        Key::minor(Pitch::class_from_str(s).unwrap())
+    }
+
+    #[test]
+    fn pivots() {
+        let pivots: Vec<_> = pivot_chords(&major("C"), &minor("E"))
+            .iter()
+            .map(|p| format!("{} = {}", p.from(), p.to()))
+            .collect();
+        assert_eq!(pivots, vec!["I = VI", "iii = i", "V = III", "vi = iv"]);
+
+        assert!(pivot_chords(&major("C"), &major("F#")).is_empty());
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(
+            modulation_paths(&major("C"), &minor("A")),
+            vec![vec![major("C"), minor("A")]]
+        );
+        assert_eq!(
+            modulation_paths(&major("C"), &major("C")),
+            vec![vec![major("C")]]
+        );
+
+        let paths = modulation_paths(&major("C"), &major("Eb"));
+        assert!(!paths.is_empty());
+        for path in &paths {
+            assert_eq!(path.len(), 4);
+            assert_eq!(path.first(), Some(&major("C")));
+            assert_eq!(path.last(), Some(&major("Eb")));
+        }
+    }
+
+    #[test]
+    fn enharmonic_target() {
+        let paths = modulation_paths(&major("C"), &major("F#"));
+        assert!(!paths.is_empty());
+        assert!(paths.iter().all(|p| p.last() == Some(&major("F#"))));
+        assert!(paths.iter().all(|p| p.len() == 7));
+    }
+}
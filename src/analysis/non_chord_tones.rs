@@ -0,0 +1,211 @@
+use crate::{
+    harmony::{Interval, Pitch, chord::Chord},
+    rhythm::Timed,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The function of a note of a melody relative to the underlying chord
+pub enum ToneClass {
+    /// The note belongs to the chord sounding at its onset
+    ChordTone,
+    /// Approached and left by step in the same direction
+    PassingTone,
+    /// Approached and left by step in opposite directions
+    NeighborTone,
+    /// Prepared by the same pitch in the previous chord and resolved down by step
+    Suspension,
+    /// Approached by leap and left by step in the opposite direction
+    Appoggiatura,
+    /// Approached by step and left by leap in the opposite direction
+    EscapeTone,
+    /// Repeated as a chord tone of the following chord
+    Anticipation,
+    /// Approached and left by the same pitch
+    Pedal,
+    /// A non-chord tone which fits none of the other classes, or a note without an underlying
+    /// chord
+    Unclassified,
+}
+
+/// Classifies each note of `melody` relative to the chords of `chords`.
+///
+/// The melody is expected to be monophonic and sorted by onset.
+/// A note is compared to the chord which sounds at its onset, see [`Timed::sounds_at`].
+/// Chord membership is tested on chromatic pitch classes, so the spelling of the melody does
+/// not matter. The motion into and out of a non-chord tone is measured in diatonic steps, where
+/// a step is one diatonic step and anything larger is a leap.
+///
+/// The returned vector contains one entry for each note in `melody`.
+///
+/// # Examples
+/// ```
+/// # use music_types::{
+/// #     analysis::{ToneClass, classify_tones},
+/// #     harmony::{chord::Chord, Pitch, ParsePitchError},
+/// #     rhythm::Timed,
+/// # };
+/// # use std::str::FromStr;
+/// let melody: Vec<_> = ["C4", "D4", "E4", "F4", "E4"]
+///     .iter()
+///     .enumerate()
+///     .map(|(i, s)| Ok(Timed::new(Pitch::from_str(s)?, i as f64, 1.0)))
+///     .collect::<Result<_, ParsePitchError>>()?;
+/// let chords = vec![Timed::new(Chord::major(Pitch::from_str("C3")?), 0.0, 5.0)];
+///
+/// assert_eq!(
+///     classify_tones(&melody, &chords),
+///     vec![
+///         ToneClass::ChordTone,
+///         ToneClass::PassingTone,
+///         ToneClass::ChordTone,
+///         ToneClass::NeighborTone,
+///         ToneClass::ChordTone,
+///     ]
+/// );
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub fn classify_tones(melody: &[Timed<Pitch>], chords: &[Timed<Chord>]) -> Vec<ToneClass> {
+    let chord_at = |time: f64| chords.iter().rev().find(|c| c.sounds_at(time));
+    let is_chord_tone = |note: &Timed<Pitch>| {
+        chord_at(note.onset).is_some_and(|chord| chord.value.contains_class(note.value))
+    };
+
+    let mut classes = Vec::with_capacity(melody.len());
+    for (i, note) in melody.iter().enumerate() {
+        let Some(chord) = chord_at(note.onset) else {
+            classes.push(ToneClass::Unclassified);
+            continue;
+        };
+        if chord.value.contains_class(note.value) {
+            classes.push(ToneClass::ChordTone);
+            continue;
+        }
+
+        let prev = i.checked_sub(1).map(|j| &melody[j]);
+        let next = melody.get(i + 1);
+        let into = prev.map(|p| note.value - p.value);
+        let out = next.map(|n| n.value - note.value);
+
+        let class = match (into, out) {
+            (Some(into), Some(out)) if into == Interval::UNISON && out == Interval::UNISON => {
+                ToneClass::Pedal
+            }
+            (_, Some(out))
+                if out == Interval::UNISON
+                    && next.is_some_and(|n| {
+                        is_chord_tone(n)
+                            && chord_at(n.onset).is_some_and(|c| c.value != chord.value)
+                    }) =>
+            {
+                ToneClass::Anticipation
+            }
+            (Some(into), Some(out))
+                if into == Interval::UNISON
+                    && out.diatonic == -1
+                    && prev.is_some_and(is_chord_tone) =>
+            {
+                ToneClass::Suspension
+            }
+            (Some(into), Some(out)) => {
+                let same_direction = into.diatonic.signum() == out.diatonic.signum();
+                match (into.diatonic.abs(), out.diatonic.abs()) {
+                    (1, 1) if same_direction => ToneClass::PassingTone,
+                    (1, 1) => ToneClass::NeighborTone,
+                    (2.., 1) if !same_direction => ToneClass::Appoggiatura,
+                    (1, 2..) if !same_direction => ToneClass::EscapeTone,
+                    _ => ToneClass::Unclassified,
+                }
+            }
+            _ => ToneClass::Unclassified,
+        };
+        classes.push(class);
+    }
+    classes
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn melody(pitches: &[&str]) -> Vec<Timed<Pitch>> {
+        pitches
+            .iter()
+            .zip(0..)
+            .map(|(s, i)| Timed::new(Pitch::from_str(s).unwrap(), f64::from(i), 1.0))
+            .collect()
+    }
+
+    fn chord(chord: fn(Pitch) -> Chord, root: &str, onset: f64, duration: f64) -> Timed<Chord> {
+        Timed::new(chord(Pitch::from_str(root).unwrap()), onset, duration)
+    }
+
+    #[test]
+    fn step_classes() {
+        let chords = vec![chord(Chord::major, "C3", 0.0, 8.0)];
+
+        let notes = melody(&["C4", "D4", "E4"]);
+        assert_eq!(classify_tones(&notes, &chords)[1], ToneClass::PassingTone);
+
+        let notes = melody(&["E4", "F4", "E4"]);
+        assert_eq!(classify_tones(&notes, &chords)[1], ToneClass::NeighborTone);
+
+        let notes = melody(&["C4", "A4", "G4"]);
+        assert_eq!(classify_tones(&notes, &chords)[1], ToneClass::Appoggiatura);
+
+        let notes = melody(&["C4", "D4", "G3"]);
+        assert_eq!(classify_tones(&notes, &chords)[1], ToneClass::EscapeTone);
+
+        let notes = melody(&["C5", "F#4", "G4"]);
+        assert_eq!(classify_tones(&notes, &chords)[1], ToneClass::Appoggiatura);
+    }
+
+    #[test]
+    fn held_classes() {
+        // F4 is held from the G7 chord into the C chord and resolves down to E4
+        let chords = vec![
+            chord(Chord::dominant_seventh, "G2", 0.0, 1.0),
+            chord(Chord::major, "C3", 1.0, 2.0),
+        ];
+        let notes = melody(&["F4", "F4", "E4"]);
+        assert_eq!(
+            classify_tones(&notes, &chords),
+            vec![
+                ToneClass::ChordTone,
+                ToneClass::Suspension,
+                ToneClass::ChordTone
+            ]
+        );
+
+        // C4 is sounded early over the G chord
+        let chords = vec![
+            chord(Chord::major, "G2", 0.0, 2.0),
+            chord(Chord::major, "C3", 2.0, 1.0),
+        ];
+        let notes = melody(&["D4", "C4", "C4"]);
+        assert_eq!(classify_tones(&notes, &chords)[1], ToneClass::Anticipation);
+
+        // G4 is repeated through chords it does not belong to
+        let chords = vec![
+            chord(Chord::major, "C3", 0.0, 1.0),
+            chord(Chord::major, "F3", 1.0, 1.0),
+            chord(Chord::major, "C3", 2.0, 1.0),
+        ];
+        let notes = melody(&["G4", "G4", "G4"]);
+        assert_eq!(
+            classify_tones(&notes, &chords),
+            vec![ToneClass::ChordTone, ToneClass::Pedal, ToneClass::ChordTone]
+        );
+    }
+
+    #[test]
+    fn without_chord() {
+        let notes = melody(&["C4", "D4"]);
+        assert_eq!(
+            classify_tones(&notes, &[]),
+            vec![ToneClass::Unclassified, ToneClass::Unclassified]
+        );
+    }
+}
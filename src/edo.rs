@@ -0,0 +1,331 @@
+//! This module contains pitches and intervals in equal divisions of the octave (EDO) other than
+//! twelve.
+//!
+//! Spelled pitches and intervals are mapped to steps of the equal division through the size of
+//! the perfect fifth: every [`Interval`] is a sum of fifths and octaves,
+//! see [`Interval::to_fifths_and_octaves`], and the fifth is tuned to the step of the equal
+//! division closest to a pure fifth.
+//! In meantone divisions like 19 or 31 EDO this keeps spelled notation meaningful: C# and Db are
+//! different steps, and arithmetic on spelled pitches works as in twelve tone equal temperament.
+//! Steps which are not reached by the spelling, like the quarter tones of 24 EDO, are notated
+//! with ups and downs.
+use std::{
+    fmt,
+    ops::{Add, Neg, Sub},
+    str::FromStr,
+};
+
+use crate::{
+    harmony::{Interval, ParsePitchError, Pitch},
+    tuning::Tuning,
+};
+
+/// log2(3/2), the size of a pure fifth in octaves
+const PURE_FIFTH: f64 = 0.584_962_500_721_156_2;
+
+/// returns the number of steps of the fifth closest to a pure fifth in `N` EDO
+#[allow(clippy::cast_possible_truncation)]
+const fn fifth_steps<const N: u16>() -> i16 {
+    (N as f64 * PURE_FIFTH + 0.5) as i16
+}
+
+/// returns the number of steps of `interval` in `N` EDO
+#[allow(clippy::cast_possible_wrap)]
+fn interval_steps<const N: u16>(interval: Interval) -> i16 {
+    let (fifths, octaves) = interval.to_fifths_and_octaves();
+    fifths * fifth_steps::<N>() + octaves * N as i16
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An interval in `N` EDO: a spelled interval together with a number of ups.
+///
+/// # Examples
+/// ```
+/// # use music_types::{edo::EdoInterval, harmony::{Interval, ParseIntervalError}};
+/// # use std::str::FromStr;
+/// let major_third = EdoInterval::<31>::from(Interval::MAJ_THIRD);
+/// assert_eq!(major_third.steps(), 10);
+/// // the augmented second is smaller than the minor third in meantone
+/// let augmented_second = EdoInterval::<31>::from(Interval::from_str("a2")?);
+/// assert_eq!(augmented_second.steps(), 7);
+/// assert_eq!(EdoInterval::<31>::from(Interval::MIN_THIRD).steps(), 8);
+/// # Ok::<(), ParseIntervalError>(())
+/// ```
+pub struct EdoInterval<const N: u16> {
+    interval: Interval,
+    ups: i16,
+}
+
+impl<const N: u16> EdoInterval<N> {
+    /// creates an interval from a spelled interval raised by `ups` steps
+    pub fn new(interval: Interval, ups: i16) -> Self {
+        Self { interval, ups }
+    }
+
+    /// returns the spelled interval
+    pub fn interval(&self) -> Interval {
+        self.interval
+    }
+
+    /// returns the number of steps by which the spelled interval is raised
+    pub fn ups(&self) -> i16 {
+        self.ups
+    }
+
+    /// returns the size of the interval in steps
+    pub fn steps(&self) -> i16 {
+        interval_steps::<N>(self.interval) + self.ups
+    }
+
+    /// returns the size of the interval in cents
+    pub fn cents(&self) -> f64 {
+        f64::from(self.steps()) * 1200.0 / f64::from(N)
+    }
+}
+
+impl<const N: u16> From<Interval> for EdoInterval<N> {
+    fn from(interval: Interval) -> Self {
+        Self::new(interval, 0)
+    }
+}
+
+impl<const N: u16> Add for EdoInterval<N> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.interval + rhs.interval, self.ups + rhs.ups)
+    }
+}
+
+impl<const N: u16> Neg for EdoInterval<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.interval, -self.ups)
+    }
+}
+
+impl<const N: u16> Sub for EdoInterval<N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A pitch in `N` EDO: a spelled pitch together with a number of ups.
+///
+/// # FromStr and Display implementation
+///
+/// Ups and downs are written as `"^"` and `"v"` in front of the pitch.
+/// ```
+/// # use music_types::{edo::EdoPitch, harmony::ParsePitchError};
+/// # use std::str::FromStr;
+/// let pitch = EdoPitch::<24>::from_str("^E4")?;
+/// assert_eq!(pitch.ups(), 1);
+/// assert_eq!(pitch.steps(), 9);
+/// assert_eq!(pitch.to_string(), "^E4");
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+///
+/// # Examples
+/// ```
+/// # use music_types::{edo::EdoPitch, harmony::{Interval, ParsePitchError}};
+/// # use std::str::FromStr;
+/// let c_sharp = EdoPitch::<31>::from_str("C#4")?;
+/// let d_flat = EdoPitch::<31>::from_str("Db4")?;
+/// assert_eq!(c_sharp.steps(), 2);
+/// assert_eq!(d_flat.steps(), 3);
+/// assert_eq!(c_sharp + Interval::FIFTH.into(), EdoPitch::from_str("G#4")?);
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub struct EdoPitch<const N: u16> {
+    pitch: Pitch,
+    ups: i16,
+}
+
+impl<const N: u16> EdoPitch<N> {
+    /// the number of steps of the perfect fifth
+    pub const FIFTH: i16 = fifth_steps::<N>();
+
+    /// the number of steps of the sharp, the difference between the chromatic and the diatonic
+    /// semitone
+    #[allow(clippy::cast_possible_wrap)]
+    pub const SHARP: i16 = 7 * Self::FIFTH - 4 * N as i16;
+
+    /// creates a pitch from a spelled pitch raised by `ups` steps
+    pub fn new(pitch: Pitch, ups: i16) -> Self {
+        Self { pitch, ups }
+    }
+
+    /// returns the spelled pitch
+    pub fn pitch(&self) -> Pitch {
+        self.pitch
+    }
+
+    /// returns the number of steps by which the spelled pitch is raised
+    pub fn ups(&self) -> i16 {
+        self.ups
+    }
+
+    /// returns the number of steps from C4
+    pub fn steps(&self) -> i16 {
+        interval_steps::<N>(self.pitch - Pitch::new(0, 0)) + self.ups
+    }
+
+    /// Converts the pitch to a frequency using the standard tuning A4 = 440Hz
+    pub fn to_frequency(&self) -> f32 {
+        self.to_frequency_tuning(440.0)
+    }
+
+    /// Converts the pitch to a frequency using the given tuning for A4
+    pub fn to_frequency_tuning(&self, a_4: f32) -> f32 {
+        let a_4_steps = Self::from(Pitch::new(5, 9)).steps();
+        let octaves = f32::from(self.steps() - a_4_steps) / f32::from(N);
+        a_4 * octaves.exp2()
+    }
+}
+
+impl<const N: u16> From<Pitch> for EdoPitch<N> {
+    fn from(pitch: Pitch) -> Self {
+        Self::new(pitch, 0)
+    }
+}
+
+impl<const N: u16> Add<EdoInterval<N>> for EdoPitch<N> {
+    type Output = Self;
+
+    fn add(self, rhs: EdoInterval<N>) -> Self::Output {
+        Self::new(self.pitch + rhs.interval, self.ups + rhs.ups)
+    }
+}
+
+impl<const N: u16> Sub<EdoInterval<N>> for EdoPitch<N> {
+    type Output = Self;
+
+    fn sub(self, rhs: EdoInterval<N>) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+impl<const N: u16> Sub for EdoPitch<N> {
+    type Output = EdoInterval<N>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        EdoInterval::new(self.pitch - rhs.pitch, self.ups - rhs.ups)
+    }
+}
+
+impl<const N: u16> fmt::Display for EdoPitch<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let arrow = if self.ups > 0 { "^" } else { "v" };
+        let arrows = arrow.repeat(self.ups.unsigned_abs().into());
+        write!(f, "{arrows}{}", self.pitch)
+    }
+}
+
+impl<const N: u16> FromStr for EdoPitch<N> {
+    type Err = ParsePitchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let pitch = s.trim_start_matches(['^', 'v']);
+        let ups = s[..s.len() - pitch.len()]
+            .chars()
+            .map(|c| if c == '^' { 1 } else { -1 })
+            .sum();
+        Ok(Self::new(Pitch::from_str(pitch)?, ups))
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The tuning of spelled pitches in `N` EDO
+///
+/// # Examples
+/// ```
+/// # use music_types::{edo::EdoTuning, harmony::{Pitch, ParsePitchError}, tuning::Tuning};
+/// # use std::str::FromStr;
+/// let tuning = EdoTuning::<31>::new(440.0);
+/// let e_sharp = tuning.frequency(Pitch::from_str("E#4")?);
+/// let f_flat = tuning.frequency(Pitch::from_str("Fb4")?);
+/// assert!(f_flat < e_sharp);
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub struct EdoTuning<const N: u16> {
+    a_4: f64,
+}
+
+impl<const N: u16> EdoTuning<N> {
+    /// creates the tuning where A4 has frequency `a_4`
+    pub fn new(a_4: f64) -> Self {
+        Self { a_4 }
+    }
+}
+
+impl<const N: u16> Default for EdoTuning<N> {
+    fn default() -> Self {
+        Self::new(440.0)
+    }
+}
+
+impl<const N: u16> Tuning for EdoTuning<N> {
+    fn frequency(&self, pitch: Pitch) -> f64 {
+        let steps =
+            EdoPitch::<N>::from(pitch).steps() - EdoPitch::<N>::from(Pitch::new(5, 9)).steps();
+        self.a_4 * (f64::from(steps) / f64::from(N)).exp2()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fifths_and_sharps() {
+        assert_eq!(EdoPitch::<12>::FIFTH, 7);
+        assert_eq!(EdoPitch::<12>::SHARP, 1);
+        assert_eq!(EdoPitch::<19>::FIFTH, 11);
+        assert_eq!(EdoPitch::<19>::SHARP, 1);
+        assert_eq!(EdoPitch::<24>::SHARP, 2);
+        assert_eq!(EdoPitch::<31>::FIFTH, 18);
+        assert_eq!(EdoPitch::<31>::SHARP, 2);
+        assert_eq!(EdoPitch::<41>::SHARP, 4);
+        assert_eq!(EdoPitch::<53>::FIFTH, 31);
+        assert_eq!(EdoPitch::<53>::SHARP, 5);
+    }
+
+    #[test]
+    fn twelve_is_chromatic() {
+        for s in ["C4", "C#4", "Db4", "B#3", "Fb2", "G##5"] {
+            let pitch = Pitch::from_str(s).unwrap();
+            assert_eq!(
+                EdoPitch::<12>::from(pitch).steps(),
+                pitch.to_chromatic().to_num()
+            );
+        }
+    }
+
+    #[test]
+    fn arithmetic() {
+        let c = EdoPitch::<31>::from_str("C4").unwrap();
+        let e = EdoPitch::<31>::from_str("E4").unwrap();
+        assert_eq!((e - c).steps(), 10);
+        assert_eq!((e - c).interval(), Interval::MAJ_THIRD);
+        assert_eq!(c + (e - c), e);
+        let octave = EdoInterval::<31>::from(Interval::OCTAVE);
+        assert_eq!(octave.steps(), 31);
+        assert_eq!((e - octave).steps(), 10 - 31);
+    }
+
+    #[test]
+    fn quarter_tones() {
+        let e_half_flat = EdoPitch::<24>::from_str("vE4").unwrap();
+        assert_eq!(e_half_flat.steps(), 7);
+        assert_eq!(e_half_flat.to_string(), "vE4");
+        assert!((EdoPitch::<24>::from_str("^A4").unwrap().to_frequency() - 452.893).abs() < 1e-3);
+        assert!(EdoPitch::<24>::from_str("^^A").is_err());
+    }
+}
@@ -3,10 +3,18 @@
 
 use std::{error::Error, fmt};
 
+pub mod chord;
 mod interval;
+mod micro_pitch;
+mod names;
 mod pitch;
 pub mod scale;
-pub use interval::{ChromaticInterval, ChromaticOctave, Interval, Octave, ParseIntervalError};
+pub use interval::{
+    ChromaticInterval, ChromaticOctave, Consonance, ConsonanceContext, Interval, Octave,
+    ParseIntervalError, Quality,
+};
+pub use micro_pitch::MicroPitch;
+pub use names::{NamingSystem, OctaveNotation, PitchFormatter};
 pub use pitch::{Accidental, ChromaticPitch, ParsePitchError, Pitch, PitchName};
 
 #[derive(Debug)]
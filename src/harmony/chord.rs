@@ -0,0 +1,270 @@
+//! this module contains types representing chords
+use std::fmt;
+
+use crate::harmony::{ChromaticOctave, Interval, Pitch};
+
+mod nashville;
+
+pub use nashville::{ChordQuality, NashvilleChart, NashvilleChord, ParseNashvilleError};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// This struct represents a chord by its root and the intervals above the root.
+///
+/// The intervals are allways sorted and start with the unison interval.
+///
+/// # Examples
+/// ```
+/// # use music_types::harmony::{chord::Chord, Pitch, ParsePitchError};
+/// # use std::str::FromStr;
+/// let chord = Chord::major(Pitch::from_str("D4")?);
+/// let pitches: Vec<_> = chord.pitches().collect();
+/// assert_eq!(
+///     pitches,
+///     vec![Pitch::from_str("D4")?, Pitch::from_str("F#4")?, Pitch::from_str("A4")?]
+/// );
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub struct Chord {
+    root: Pitch,
+    intervals: Vec<Interval>,
+}
+
+impl Chord {
+    /// Creates a new chord.
+    /// This function sorts the intervals and adds a unison at the start if needed.
+    pub fn new(root: Pitch, mut intervals: Vec<Interval>) -> Self {
+        intervals.sort_by(Interval::cmp_diatonic);
+        intervals.dedup();
+        if intervals.first() != Some(&Interval::UNISON) {
+            intervals.insert(0, Interval::UNISON);
+        }
+        Self { root, intervals }
+    }
+
+    /// returns the root of the chord
+    pub fn root(&self) -> Pitch {
+        self.root
+    }
+
+    /// returns the intervals above the root
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    /// returns an iterator over the pitches of the chord starting from the root
+    pub fn pitches(&self) -> impl Iterator<Item = Pitch> + '_ {
+        self.intervals.iter().map(|i| self.root + i)
+    }
+
+    /// returns true if a pitch of the chord has the same chromatic pitch class as `pitch`
+    ///
+    /// The octave and the spelling of `pitch` are ignored.
+    pub fn contains_class(&self, pitch: Pitch) -> bool {
+        let class = pitch.to_chromatic() % ChromaticOctave;
+        self.pitches()
+            .any(|p| p.to_chromatic() % ChromaticOctave == class)
+    }
+
+    /// returns the quality of the triad formed by the root, the third and the fifth of the chord
+    ///
+    /// Returns `None` if the chord has no third or no fifth or if they form no standard triad.
+    pub fn triad_quality(&self) -> Option<TriadQuality> {
+        let third = self.intervals.iter().find(|i| i.diatonic == 2)?;
+        let fifth = self.intervals.iter().find(|i| i.diatonic == 4)?;
+        match (third.chromatic, fifth.chromatic) {
+            (4, 7) => Some(TriadQuality::Major),
+            (3, 7) => Some(TriadQuality::Minor),
+            (3, 6) => Some(TriadQuality::Diminished),
+            (4, 8) => Some(TriadQuality::Augmented),
+            _ => None,
+        }
+    }
+
+    /// returns the same chord with a different root
+    pub fn transpose(&self, interval: Interval) -> Self {
+        Self {
+            root: self.root + interval,
+            intervals: self.intervals.clone(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The quality of a triad
+#[allow(missing_docs)]
+pub enum TriadQuality {
+    Major,
+    Minor,
+    Diminished,
+    Augmented,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A roman numeral naming a triad by the scale degree of its root
+///
+/// # Display implementation
+/// Major and augmented triads are displayed in upper case, minor and diminished triads in lower
+/// case. Diminished triads are followed by `°` and augmented triads by `+`.
+/// ```
+/// # use music_types::harmony::chord::{RomanNumeral, TriadQuality};
+/// assert_eq!(RomanNumeral::new(5, TriadQuality::Major).to_string(), "V");
+/// assert_eq!(RomanNumeral::new(2, TriadQuality::Minor).to_string(), "ii");
+/// assert_eq!(RomanNumeral::new(7, TriadQuality::Diminished).to_string(), "vii°");
+/// assert_eq!(RomanNumeral::new(3, TriadQuality::Augmented).to_string(), "III+");
+/// ```
+pub struct RomanNumeral {
+    degree: u8,
+    quality: TriadQuality,
+}
+
+impl RomanNumeral {
+    /// creates a roman numeral from the scale degree, where the tonic is degree 1
+    ///
+    /// # Panics
+    /// Panics if the degree is not in 1..=7
+    pub fn new(degree: u8, quality: TriadQuality) -> Self {
+        assert!(
+            (1..=7).contains(&degree),
+            "scale degree of a roman numeral must be in 1..=7"
+        );
+        Self { degree, quality }
+    }
+
+    /// returns the scale degree, where the tonic is degree 1
+    pub fn degree(&self) -> u8 {
+        self.degree
+    }
+
+    /// returns the quality of the triad
+    pub fn quality(&self) -> TriadQuality {
+        self.quality
+    }
+}
+
+impl fmt::Display for RomanNumeral {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let numeral = ["I", "II", "III", "IV", "V", "VI", "VII"][usize::from(self.degree - 1)];
+        match self.quality {
+            TriadQuality::Major => write!(f, "{numeral}"),
+            TriadQuality::Augmented => write!(f, "{numeral}+"),
+            TriadQuality::Minor => write!(f, "{}", numeral.to_lowercase()),
+            TriadQuality::Diminished => write!(f, "{}°", numeral.to_lowercase()),
+        }
+    }
+}
+
+/// Common chords
+#[allow(missing_docs)]
+impl Chord {
+    pub fn major(root: Pitch) -> Self {
+        Self::new(root, vec![Interval::MAJ_THIRD, Interval::FIFTH])
+    }
+
+    pub fn minor(root: Pitch) -> Self {
+        Self::new(root, vec![Interval::MIN_THIRD, Interval::FIFTH])
+    }
+
+    pub fn diminished(root: Pitch) -> Self {
+        Self::new(root, vec![Interval::MIN_THIRD, Interval::DIM_FIFTH])
+    }
+
+    pub fn augmented(root: Pitch) -> Self {
+        Self::new(root, vec![Interval::MAJ_THIRD, Interval::new(8, 4)])
+    }
+
+    pub fn dominant_seventh(root: Pitch) -> Self {
+        Self::new(
+            root,
+            vec![Interval::MAJ_THIRD, Interval::FIFTH, Interval::MIN_SEVENTH],
+        )
+    }
+
+    pub fn major_seventh(root: Pitch) -> Self {
+        Self::new(
+            root,
+            vec![Interval::MAJ_THIRD, Interval::FIFTH, Interval::MAJ_SEVENTH],
+        )
+    }
+
+    pub fn minor_seventh(root: Pitch) -> Self {
+        Self::new(
+            root,
+            vec![Interval::MIN_THIRD, Interval::FIFTH, Interval::MIN_SEVENTH],
+        )
+    }
+
+    pub fn half_diminished_seventh(root: Pitch) -> Self {
+        Self::new(
+            root,
+            vec![
+                Interval::MIN_THIRD,
+                Interval::DIM_FIFTH,
+                Interval::MIN_SEVENTH,
+            ],
+        )
+    }
+
+    pub fn diminished_seventh(root: Pitch) -> Self {
+        Self::new(
+            root,
+            vec![
+                Interval::MIN_THIRD,
+                Interval::DIM_FIFTH,
+                Interval::new(9, 6),
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn new_normalizes() {
+        let root = Pitch::from_str("C4").unwrap();
+        assert_eq!(
+            Chord::new(root, vec![Interval::FIFTH, Interval::MAJ_THIRD]),
+            Chord::major(root)
+        );
+    }
+
+    #[test]
+    fn contains_class() {
+        let chord = Chord::dominant_seventh(Pitch::from_str("G3").unwrap());
+        assert!(chord.contains_class(Pitch::from_str("F5").unwrap()));
+        assert!(chord.contains_class(Pitch::from_str("B2").unwrap()));
+        assert!(chord.contains_class(Pitch::from_str("Cb4").unwrap()));
+        assert!(!chord.contains_class(Pitch::from_str("C4").unwrap()));
+    }
+
+    #[test]
+    fn triad_quality() {
+        let root = Pitch::from_str("Eb4").unwrap();
+        assert_eq!(
+            Chord::major(root).triad_quality(),
+            Some(TriadQuality::Major)
+        );
+        assert_eq!(
+            Chord::minor(root).triad_quality(),
+            Some(TriadQuality::Minor)
+        );
+        assert_eq!(
+            Chord::half_diminished_seventh(root).triad_quality(),
+            Some(TriadQuality::Diminished)
+        );
+        assert_eq!(
+            Chord::augmented(root).triad_quality(),
+            Some(TriadQuality::Augmented)
+        );
+        assert_eq!(
+            Chord::new(root, vec![Interval::FOURTH, Interval::FIFTH]).triad_quality(),
+            None
+        );
+    }
+}
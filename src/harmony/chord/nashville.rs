@@ -0,0 +1,440 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use crate::harmony::{
+    Interval, Pitch,
+    scale::{Key, ScaleDegree},
+};
+
+use super::Chord;
+
+#[derive(Debug)]
+/// Error that may occur when parsing a Nashville chart.
+pub enum ParseNashvilleError {
+    /// A chord without a valid scale degree
+    InvalidDegree(String),
+    /// A chord with an unknown quality
+    InvalidQuality(String),
+    /// An unclosed diamond
+    UnclosedDiamond(String),
+}
+
+impl fmt::Display for ParseNashvilleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDegree(s) => write!(f, "could not parse scale degree `{s}`"),
+            Self::InvalidQuality(s) => write!(f, "could not parse chord quality `{s}`"),
+            Self::UnclosedDiamond(s) => write!(f, "diamond is not closed in `{s}`"),
+        }
+    }
+}
+
+impl Error for ParseNashvilleError {}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The quality of a chord as written in a chord symbol
+///
+/// # FromStr and Display implementation
+/// The quality is written after the root of the chord. Besides the displayed suffixes `m`,
+/// `°`, `+`, `7`, `maj7`, `m7`, `ø7`, `°7`, `sus4` and `sus2`, the alternatives `-`, `o`,
+/// `dim`, `aug`, `M7`, `Δ`, `Δ7`, `-7`, `ø`, `m7b5`, `o7`, `dim7` and `sus` are parsed.
+pub enum ChordQuality {
+    /// A major triad, written without a suffix
+    #[default]
+    Major,
+    /// A minor triad
+    Minor,
+    /// A diminished triad
+    Diminished,
+    /// An augmented triad
+    Augmented,
+    /// A major triad with a minor seventh
+    DominantSeventh,
+    /// A major triad with a major seventh
+    MajorSeventh,
+    /// A minor triad with a minor seventh
+    MinorSeventh,
+    /// A diminished triad with a minor seventh
+    HalfDiminishedSeventh,
+    /// A diminished triad with a diminished seventh
+    DiminishedSeventh,
+    /// A fifth with a fourth in place of the third
+    SuspendedFourth,
+    /// A fifth with a second in place of the third
+    SuspendedSecond,
+}
+
+impl ChordQuality {
+    /// returns the chord of this quality on `root`
+    pub fn chord(self, root: Pitch) -> Chord {
+        match self {
+            Self::Major => Chord::major(root),
+            Self::Minor => Chord::minor(root),
+            Self::Diminished => Chord::diminished(root),
+            Self::Augmented => Chord::augmented(root),
+            Self::DominantSeventh => Chord::dominant_seventh(root),
+            Self::MajorSeventh => Chord::major_seventh(root),
+            Self::MinorSeventh => Chord::minor_seventh(root),
+            Self::HalfDiminishedSeventh => Chord::half_diminished_seventh(root),
+            Self::DiminishedSeventh => Chord::diminished_seventh(root),
+            Self::SuspendedFourth => Chord::new(root, vec![Interval::FOURTH, Interval::FIFTH]),
+            Self::SuspendedSecond => Chord::new(root, vec![Interval::MAJ_SECOND, Interval::FIFTH]),
+        }
+    }
+}
+
+impl fmt::Display for ChordQuality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suffix = match self {
+            Self::Major => "",
+            Self::Minor => "m",
+            Self::Diminished => "°",
+            Self::Augmented => "+",
+            Self::DominantSeventh => "7",
+            Self::MajorSeventh => "maj7",
+            Self::MinorSeventh => "m7",
+            Self::HalfDiminishedSeventh => "ø7",
+            Self::DiminishedSeventh => "°7",
+            Self::SuspendedFourth => "sus4",
+            Self::SuspendedSecond => "sus2",
+        };
+        write!(f, "{suffix}")
+    }
+}
+
+impl FromStr for ChordQuality {
+    type Err = ParseNashvilleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Ok(Self::Major),
+            "m" | "-" => Ok(Self::Minor),
+            "°" | "o" | "dim" => Ok(Self::Diminished),
+            "+" | "aug" => Ok(Self::Augmented),
+            "7" => Ok(Self::DominantSeventh),
+            "maj7" | "M7" | "Δ" | "Δ7" => Ok(Self::MajorSeventh),
+            "m7" | "-7" => Ok(Self::MinorSeventh),
+            "ø" | "ø7" | "m7b5" => Ok(Self::HalfDiminishedSeventh),
+            "°7" | "o7" | "dim7" => Ok(Self::DiminishedSeventh),
+            "sus" | "sus4" => Ok(Self::SuspendedFourth),
+            "sus2" => Ok(Self::SuspendedSecond),
+            _ => Err(ParseNashvilleError::InvalidQuality(s.to_string())),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A chord of the Nashville number system
+///
+/// The root and the bass are scale degrees of the major scale of the key, so `b7` is the major
+/// chord on the lowered seventh degree in any key.
+///
+/// # FromStr and Display implementation
+/// A chord is written as its root degree with an optional accidental, its [`ChordQuality`]
+/// and optionally `/` followed by the bass degree, like `5/7`, `6m` or `b7`.
+/// A diamond, which marks a chord that is struck once and held, is written by enclosing the
+/// chord in `<` and `>`. A push, which anticipates the chord before the beat, is written as a
+/// leading `^`.
+/// ```
+/// # use music_types::harmony::chord::{ChordQuality, NashvilleChord, ParseNashvilleError};
+/// # use std::str::FromStr;
+/// let chord = NashvilleChord::from_str("^<b7sus4/1>")?;
+/// assert_eq!(chord.quality(), ChordQuality::SuspendedFourth);
+/// assert!(chord.is_push() && chord.is_diamond());
+/// assert_eq!(chord.to_string(), "^<b7sus4/1>");
+/// # Ok::<(), ParseNashvilleError>(())
+/// ```
+pub struct NashvilleChord {
+    root: ScaleDegree,
+    quality: ChordQuality,
+    bass: Option<ScaleDegree>,
+    diamond: bool,
+    push: bool,
+}
+
+impl NashvilleChord {
+    /// creates a chord on the `root` degree without bass, diamond or push
+    pub fn new(root: ScaleDegree, quality: ChordQuality) -> Self {
+        Self {
+            root,
+            quality,
+            bass: None,
+            diamond: false,
+            push: false,
+        }
+    }
+
+    /// sets the bass degree, for slash chords
+    pub fn with_bass(mut self, bass: ScaleDegree) -> Self {
+        self.bass = Some(bass);
+        self
+    }
+
+    /// sets whether the chord is a diamond
+    pub fn with_diamond(mut self, diamond: bool) -> Self {
+        self.diamond = diamond;
+        self
+    }
+
+    /// sets whether the chord is pushed
+    pub fn with_push(mut self, push: bool) -> Self {
+        self.push = push;
+        self
+    }
+
+    /// returns the degree of the root in the major scale
+    pub fn root(&self) -> ScaleDegree {
+        self.root
+    }
+
+    /// returns the quality of the chord
+    pub fn quality(&self) -> ChordQuality {
+        self.quality
+    }
+
+    /// returns the degree of the bass in the major scale, if it differs from the root
+    pub fn bass(&self) -> Option<ScaleDegree> {
+        self.bass
+    }
+
+    /// returns true if the chord is a diamond
+    pub fn is_diamond(&self) -> bool {
+        self.diamond
+    }
+
+    /// returns true if the chord is pushed
+    pub fn is_push(&self) -> bool {
+        self.push
+    }
+
+    /// returns the root of the chord in the major key on `tonic`, as a pitch class
+    pub fn root_in(&self, tonic: Pitch) -> Pitch {
+        Self::degree_in(self.root, tonic)
+    }
+
+    /// returns the bass of the chord in the major key on `tonic`, as a pitch class
+    ///
+    /// Without a bass degree this is the root.
+    pub fn bass_in(&self, tonic: Pitch) -> Pitch {
+        Self::degree_in(self.bass.unwrap_or(self.root), tonic)
+    }
+
+    /// returns the chord in the major key on `tonic`, the bass is ignored
+    pub fn chord_in(&self, tonic: Pitch) -> Chord {
+        self.quality.chord(self.root_in(tonic))
+    }
+
+    /// Returns the chord symbol in the major key on `tonic`, like `G/B` for `5/7` in C.
+    ///
+    /// Diamonds and pushes are kept.
+    pub fn symbol_in(&self, tonic: Pitch) -> String {
+        let root = self.root_in(tonic);
+        let mut symbol = format!("{}{}{}", root.pitch_name(), root.accidental(), self.quality);
+        if self.bass.is_some() {
+            let bass = self.bass_in(tonic);
+            symbol = format!("{symbol}/{}{}", bass.pitch_name(), bass.accidental());
+        }
+        self.decorate(&symbol)
+    }
+
+    /// the pitch class on `degree` of the major key on `tonic`
+    fn degree_in(degree: ScaleDegree, tonic: Pitch) -> Pitch {
+        Key::major(tonic)
+            .degree_pitch(degree)
+            .expect("the major scale has all seven degrees")
+    }
+
+    /// adds the diamond and the push to a symbol
+    fn decorate(&self, symbol: &str) -> String {
+        let symbol = if self.diamond {
+            format!("<{symbol}>")
+        } else {
+            symbol.to_string()
+        };
+        if self.push {
+            format!("^{symbol}")
+        } else {
+            symbol
+        }
+    }
+
+    /// parses a scale degree without the caret
+    fn parse_degree(s: &str) -> Result<ScaleDegree, ParseNashvilleError> {
+        let invalid = || ParseNashvilleError::InvalidDegree(s.to_string());
+        if s.starts_with('^') {
+            return Err(invalid());
+        }
+        ScaleDegree::from_str(s).map_err(|_| invalid())
+    }
+}
+
+impl fmt::Display for NashvilleChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let degree = |d: ScaleDegree| format!("{}{}", d.accidental(), d.degree());
+        let mut symbol = format!("{}{}", degree(self.root), self.quality);
+        if let Some(bass) = self.bass {
+            symbol = format!("{symbol}/{}", degree(bass));
+        }
+        write!(f, "{}", self.decorate(&symbol))
+    }
+}
+
+impl FromStr for NashvilleChord {
+    type Err = ParseNashvilleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (push, rest) = s.strip_prefix('^').map_or((false, s), |rest| (true, rest));
+        let (diamond, rest) = match rest.strip_prefix('<') {
+            Some(rest) => (
+                true,
+                rest.strip_suffix('>')
+                    .ok_or_else(|| ParseNashvilleError::UnclosedDiamond(s.to_string()))?,
+            ),
+            None => (false, rest),
+        };
+        let (chord, bass) = match rest.split_once('/') {
+            Some((chord, bass)) => (chord, Some(Self::parse_degree(bass)?)),
+            None => (rest, None),
+        };
+        // the degree ends after the first digit, so that 17 is a dominant seventh on 1
+        let digit = chord
+            .find(|c: char| c.is_ascii_digit())
+            .ok_or_else(|| ParseNashvilleError::InvalidDegree(chord.to_string()))?;
+        let root = Self::parse_degree(&chord[..=digit])?;
+        let quality = ChordQuality::from_str(&chord[digit + 1..])?;
+        Ok(Self {
+            root,
+            quality,
+            bass,
+            diamond,
+            push,
+        })
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A chart of the Nashville number system, a sequence of key independent chords
+///
+/// # FromStr and Display implementation
+/// The chords are separated by whitespace, see [`NashvilleChord`].
+///
+/// # Examples
+/// ```
+/// # use music_types::harmony::{chord::{NashvilleChart, ParseNashvilleError}, Pitch};
+/// # use std::str::FromStr;
+/// let chart = NashvilleChart::from_str("1 4 5/7 6m b7")?;
+/// let g = Pitch::class_from_str("G").unwrap();
+/// assert_eq!(chart.symbols_in(g), ["G", "C", "D/F#", "Em", "F"]);
+/// let e_flat = Pitch::class_from_str("Eb").unwrap();
+/// assert_eq!(chart.symbols_in(e_flat), ["Eb", "Ab", "Bb/D", "Cm", "Db"]);
+/// # Ok::<(), ParseNashvilleError>(())
+/// ```
+pub struct NashvilleChart(Vec<NashvilleChord>);
+
+impl NashvilleChart {
+    /// creates a chart from its chords
+    pub fn new(chords: Vec<NashvilleChord>) -> Self {
+        Self(chords)
+    }
+
+    /// returns the chords of the chart
+    pub fn chords(&self) -> &[NashvilleChord] {
+        &self.0
+    }
+
+    /// returns the chords in the major key on `tonic`, see [`NashvilleChord::chord_in`]
+    pub fn chords_in(&self, tonic: Pitch) -> Vec<Chord> {
+        self.0.iter().map(|c| c.chord_in(tonic)).collect()
+    }
+
+    /// returns the chord symbols in the major key on `tonic`, see
+    /// [`NashvilleChord::symbol_in`]
+    pub fn symbols_in(&self, tonic: Pitch) -> Vec<String> {
+        self.0.iter().map(|c| c.symbol_in(tonic)).collect()
+    }
+}
+
+impl fmt::Display for NashvilleChart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chords: Vec<_> = self.0.iter().map(ToString::to_string).collect();
+        write!(f, "{}", chords.join(" "))
+    }
+}
+
+impl FromStr for NashvilleChart {
+    type Err = ParseNashvilleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split_whitespace()
+            .map(NashvilleChord::from_str)
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::harmony::Accidental;
+
+    #[test]
+    fn parse_chords() {
+        let chord = |s: &str| NashvilleChord::from_str(s).unwrap();
+        let degree = |d: u8, shift: i16| ScaleDegree::new(d, Accidental::new(shift));
+        assert_eq!(
+            chord("1"),
+            NashvilleChord::new(degree(1, 0), ChordQuality::Major)
+        );
+        assert_eq!(
+            chord("5/7"),
+            NashvilleChord::new(degree(5, 0), ChordQuality::Major).with_bass(degree(7, 0))
+        );
+        assert_eq!(
+            chord("#4ø"),
+            NashvilleChord::new(degree(4, 1), ChordQuality::HalfDiminishedSeventh)
+        );
+        assert_eq!(chord("17").quality(), ChordQuality::DominantSeventh);
+        assert_eq!(chord("2-7").quality(), ChordQuality::MinorSeventh);
+        assert_eq!(chord("4/b7").bass(), Some(degree(7, -1)));
+        assert!(chord("<1>").is_diamond());
+        assert!(chord("^4").is_push());
+
+        for s in ["", "m", "8", "1x", "<1", "4/", "4/8", "1/^2", "^^1"] {
+            assert!(NashvilleChord::from_str(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let chart = "1 ^4 <5/7> 6m b7 #4ø7 2m7 1maj7 5sus4 7° b3+ 1sus2 7°7 ^<4/1>";
+        assert_eq!(NashvilleChart::from_str(chart).unwrap().to_string(), chart);
+        for quality in [
+            "", "m", "°", "+", "7", "maj7", "m7", "ø7", "°7", "sus4", "sus2",
+        ] {
+            assert_eq!(
+                ChordQuality::from_str(quality).unwrap().to_string(),
+                quality
+            );
+        }
+    }
+
+    #[test]
+    fn transpose() {
+        let chart = NashvilleChart::from_str("1 6m7 2m7 5 ^<b7> 4/5").unwrap();
+        let symbols = |tonic: &str| chart.symbols_in(Pitch::class_from_str(tonic).unwrap());
+        assert_eq!(symbols("C"), ["C", "Am7", "Dm7", "G", "^<Bb>", "F/G"]);
+        assert_eq!(symbols("F#"), ["F#", "D#m7", "G#m7", "C#", "^<E>", "B/C#"]);
+        assert_eq!(
+            symbols("Db"),
+            ["Db", "Bbm7", "Ebm7", "Ab", "^<Cb>", "Gb/Ab"]
+        );
+
+        let chords = chart.chords_in(Pitch::class_from_str("A").unwrap());
+        assert_eq!(
+            chords[1],
+            Chord::minor_seventh(Pitch::from_str("F#4").unwrap())
+        );
+    }
+}
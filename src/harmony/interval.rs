@@ -5,10 +5,14 @@ use crate::{
     harmony::pitch::{ChromaticPitch, Pitch},
 };
 
+mod consonance;
 mod display;
 mod parse;
+mod quality;
 
+pub use consonance::{Consonance, ConsonanceContext};
 pub use parse::ParseIntervalError;
+pub use quality::Quality;
 
 macro_rules! impl_op_for_refs {
     ($t:ty, $trait:ident, $method:ident) => {
@@ -277,6 +281,30 @@ impl Interval {
         }
     }
 
+    /// Decomposes the interval into a stack of perfect fifths and octaves.
+    ///
+    /// Returns `(fifths, octaves)` such that the interval is `fifths` perfect fifths plus
+    /// `octaves` octaves. This decomposition is unique for every interval and is the basis of
+    /// tuning the interval in any regular temperament.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{Interval, ParseIntervalError};
+    /// # use std::str::FromStr;
+    /// assert_eq!(Interval::FIFTH.to_fifths_and_octaves(), (1, 0));
+    /// assert_eq!(Interval::MAJ_SECOND.to_fifths_and_octaves(), (2, -1));
+    /// assert_eq!(Interval::from_str("a1")?.to_fifths_and_octaves(), (7, -4));
+    /// # Ok::<(), ParseIntervalError>(())
+    /// ```
+    pub fn to_fifths_and_octaves(&self) -> (i16, i16) {
+        // a fifth is (4, 7) and an octave is (7, 12) in (diatonic, chromatic) steps,
+        // the matrix formed by them has determinant -1 so its inverse is integral
+        (
+            7 * self.chromatic - 12 * self.diatonic,
+            7 * self.diatonic - 4 * self.chromatic,
+        )
+    }
+
     fn has_perfect(diatonic_steps: i16) -> bool {
         match diatonic_steps.rem_euclid(7) {
             0 | 3 | 4 => true,
@@ -0,0 +1,95 @@
+use crate::harmony::{Interval, Octave};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The classification of an interval in traditional counterpoint
+pub enum Consonance {
+    /// Unisons, fifths and octaves, and fourths between upper voices
+    Perfect,
+    /// Major and minor thirds and sixths
+    Imperfect,
+    /// All other intervals, including augmented and diminished ones
+    Dissonant,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The context of an interval, which decides how the perfect fourth is classified
+pub enum ConsonanceContext {
+    /// The interval sounds between two upper voices, the perfect fourth is a perfect consonance
+    #[default]
+    Upper,
+    /// The interval sounds above the bass, the perfect fourth is a dissonance
+    AboveBass,
+}
+
+impl Interval {
+    /// Classifies the interval as perfect or imperfect consonance or as dissonance.
+    ///
+    /// Compound intervals are classified like their simple counterparts, and descending
+    /// intervals like the ascending ones. Enharmonic spellings matter, so the augmented second
+    /// is a dissonance although it sounds like a minor third.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{Consonance, ConsonanceContext, Interval, ParseIntervalError};
+    /// # use std::str::FromStr;
+    /// let context = ConsonanceContext::default();
+    /// assert_eq!(Interval::from_str("j10")?.consonance(context), Consonance::Imperfect);
+    /// assert_eq!(Interval::from_str("a2")?.consonance(context), Consonance::Dissonant);
+    /// assert_eq!(Interval::FOURTH.consonance(context), Consonance::Perfect);
+    /// assert_eq!(
+    ///     Interval::FOURTH.consonance(ConsonanceContext::AboveBass),
+    ///     Consonance::Dissonant
+    /// );
+    /// # Ok::<(), ParseIntervalError>(())
+    /// ```
+    pub fn consonance(&self, context: ConsonanceContext) -> Consonance {
+        let simple = self.ascending() % Octave;
+        match (simple.diatonic, simple.chromatic) {
+            (0, 0) | (4, 7) => Consonance::Perfect,
+            (3, 5) if context == ConsonanceContext::Upper => Consonance::Perfect,
+            (2, 3 | 4) | (5, 8 | 9) => Consonance::Imperfect,
+            _ => Consonance::Dissonant,
+        }
+    }
+
+    /// returns true if the interval is a perfect or imperfect consonance in the given context
+    pub fn is_consonant(&self, context: ConsonanceContext) -> bool {
+        self.consonance(context) != Consonance::Dissonant
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn classification() {
+        let classify = |s: &str| {
+            Interval::from_str(s)
+                .unwrap()
+                .consonance(ConsonanceContext::default())
+        };
+        for s in ["1", "5", "8", "12", "15", "4"] {
+            assert_eq!(classify(s), Consonance::Perfect, "{s}");
+        }
+        for s in ["m3", "j3", "m6", "j6", "m10", "j13"] {
+            assert_eq!(classify(s), Consonance::Imperfect, "{s}");
+        }
+        for s in ["m2", "j2", "a4", "d5", "m7", "j7", "a5", "d4", "a1", "j9"] {
+            assert_eq!(classify(s), Consonance::Dissonant, "{s}");
+        }
+        assert_eq!(
+            (-Interval::MAJ_SIXTH).consonance(ConsonanceContext::AboveBass),
+            Consonance::Imperfect
+        );
+        assert!(
+            !Interval::from_str("11")
+                .unwrap()
+                .is_consonant(ConsonanceContext::AboveBass)
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use std::fmt::{self, Display};
+
+use crate::{div_remainder, harmony::Octave};
+
+use super::Interval;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The quality of an interval
+///
+/// Diminished and augmented qualities count how often the interval is diminished or augmented,
+/// so a doubly diminished fourth has quality `Diminished(2)`.
+pub enum Quality {
+    /// A diminished interval, smaller than minor or perfect
+    Diminished(u16),
+    /// A minor second, third, sixth or seventh
+    Minor,
+    /// A perfect unison, fourth, fifth or octave
+    Perfect,
+    /// A major second, third, sixth or seventh
+    Major,
+    /// An augmented interval, larger than major or perfect
+    Augmented(u16),
+}
+
+impl Display for Quality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (count, name) = match self {
+            Self::Diminished(count) => (*count, "diminished"),
+            Self::Minor => return write!(f, "minor"),
+            Self::Perfect => return write!(f, "perfect"),
+            Self::Major => return write!(f, "major"),
+            Self::Augmented(count) => (*count, "augmented"),
+        };
+        match count {
+            0 | 1 => write!(f, "{name}"),
+            2 => write!(f, "doubly {name}"),
+            3 => write!(f, "triply {name}"),
+            _ => write!(f, "{count} times {name}"),
+        }
+    }
+}
+
+/// the english ordinal of an interval number
+fn ordinal(number: i16) -> String {
+    const NAMES: [&str; 15] = [
+        "unison",
+        "second",
+        "third",
+        "fourth",
+        "fifth",
+        "sixth",
+        "seventh",
+        "octave",
+        "ninth",
+        "tenth",
+        "eleventh",
+        "twelfth",
+        "thirteenth",
+        "fourteenth",
+        "fifteenth",
+    ];
+    if let Some(name) = usize::try_from(number - 1).ok().and_then(|i| NAMES.get(i)) {
+        return (*name).to_string();
+    }
+    let suffix = match (number % 10, number % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{number}{suffix}")
+}
+
+impl Interval {
+    /// returns the ascending interval with the same size
+    pub(super) fn ascending(self) -> Self {
+        if self.diatonic < 0 { -self } else { self }
+    }
+
+    /// Returns the interval number, which is negative for descending intervals.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{Interval, ParseIntervalError};
+    /// # use std::str::FromStr;
+    /// assert_eq!(Interval::UNISON.number(), 1);
+    /// assert_eq!(Interval::from_str("a11")?.number(), 11);
+    /// assert_eq!(Interval::from_str("-m3")?.number(), -3);
+    /// # Ok::<(), ParseIntervalError>(())
+    /// ```
+    pub fn number(&self) -> i16 {
+        if self.diatonic < 0 {
+            self.diatonic - 1
+        } else {
+            self.diatonic + 1
+        }
+    }
+
+    /// Returns the quality of the interval, descending intervals have the quality of the
+    /// corresponding ascending interval.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{Interval, ParseIntervalError, Quality};
+    /// # use std::str::FromStr;
+    /// assert_eq!(Interval::MAJ_THIRD.quality(), Quality::Major);
+    /// assert_eq!(Interval::from_str("-8")?.quality(), Quality::Perfect);
+    /// assert_eq!(Interval::from_str("(-3)4")?.quality(), Quality::Diminished(2));
+    /// assert_eq!(Interval::from_str("a6")?.quality(), Quality::Augmented(1));
+    /// # Ok::<(), ParseIntervalError>(())
+    /// ```
+    pub fn quality(&self) -> Quality {
+        let ascending = self.ascending();
+        if Self::has_perfect(ascending.diatonic) {
+            let mismatch =
+                ascending.chromatic - Self::to_chromatic_steps_perfect(ascending.diatonic);
+            match mismatch {
+                (i16::MIN..=-1) => Quality::Diminished(mismatch.unsigned_abs()),
+                0 => Quality::Perfect,
+                (1..=i16::MAX) => Quality::Augmented(mismatch.unsigned_abs()),
+            }
+        } else {
+            let mismatch = ascending.chromatic - Self::to_chromatic_steps_minor(ascending.diatonic);
+            match mismatch {
+                (i16::MIN..=-1) => Quality::Diminished(mismatch.unsigned_abs()),
+                0 => Quality::Minor,
+                1 => Quality::Major,
+                (2..=i16::MAX) => Quality::Augmented(mismatch.unsigned_abs() - 1),
+            }
+        }
+    }
+
+    /// Splits the interval into whole octaves and a simple interval.
+    ///
+    /// The simple interval spans less than an octave and has the direction of the interval, so
+    /// that `octaves` octaves plus the simple interval is the interval again.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{Interval, ParseIntervalError};
+    /// # use std::str::FromStr;
+    /// assert_eq!(Interval::from_str("a11")?.split_octaves(), (1, Interval::AUG_FOURTH));
+    /// assert_eq!(Interval::from_str("-j10")?.split_octaves(), (-1, -Interval::MAJ_THIRD));
+    /// assert_eq!(Interval::OCTAVE.split_octaves(), (1, Interval::UNISON));
+    /// # Ok::<(), ParseIntervalError>(())
+    /// ```
+    pub fn split_octaves(&self) -> (i16, Self) {
+        let ascending = self.ascending();
+        let (octaves, _) = div_remainder(ascending.diatonic, 7);
+        let simple = ascending % Octave;
+        if self.diatonic < 0 {
+            (-octaves, -simple)
+        } else {
+            (octaves, simple)
+        }
+    }
+
+    /// Returns the inversion of the interval.
+    ///
+    /// The inversion completes the interval to the next octave above it, so the inversion of a
+    /// compound interval is simple. Unisons and octaves invert into each other and descending
+    /// intervals invert into descending intervals.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{Interval, ParseIntervalError};
+    /// # use std::str::FromStr;
+    /// assert_eq!(Interval::MAJ_THIRD.inversion(), Interval::MIN_SIXTH);
+    /// assert_eq!(Interval::from_str("a11")?.inversion(), Interval::DIM_FIFTH);
+    /// assert_eq!(Interval::UNISON.inversion(), Interval::OCTAVE);
+    /// assert_eq!(Interval::OCTAVE.inversion(), Interval::UNISON);
+    /// assert_eq!((-Interval::FOURTH).inversion(), -Interval::FIFTH);
+    /// # Ok::<(), ParseIntervalError>(())
+    /// ```
+    pub fn inversion(&self) -> Self {
+        let ascending = self.ascending();
+        // the number of octaves needed to reach above the interval, at least one
+        let octaves = ((ascending.diatonic + 6) / 7).max(1);
+        let inversion = Self::new(12 * octaves, 7 * octaves) - ascending;
+        if self.diatonic < 0 {
+            -inversion
+        } else {
+            inversion
+        }
+    }
+
+    /// Returns true if both intervals span the same number of half steps, like the augmented
+    /// second and the minor third.
+    pub fn is_enharmonic(&self, other: &Self) -> bool {
+        self.chromatic == other.chromatic
+    }
+
+    /// Returns the english name of the interval.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{Interval, ParseIntervalError};
+    /// # use std::str::FromStr;
+    /// assert_eq!(Interval::from_str("a11")?.long_name(), "augmented eleventh");
+    /// assert_eq!(Interval::from_str("(-3)4")?.long_name(), "doubly diminished fourth");
+    /// assert_eq!(Interval::from_str("-m3")?.long_name(), "descending minor third");
+    /// assert_eq!(Interval::from_str("j23")?.long_name(), "major 23rd");
+    /// # Ok::<(), ParseIntervalError>(())
+    /// ```
+    pub fn long_name(&self) -> String {
+        let direction = if self.diatonic < 0 { "descending " } else { "" };
+        format!(
+            "{direction}{} {}",
+            self.quality(),
+            ordinal(self.ascending().number())
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn quality_matches_display() {
+        for s in [
+            "1", "d1", "a1", "m2", "j2", "(-3)4", "(3)5", "a6", "d7", "(4)3", "j9", "d8",
+        ] {
+            let interval = Interval::from_str(s).unwrap();
+            let quality = match interval.quality() {
+                Quality::Diminished(1) => "d".to_string(),
+                Quality::Diminished(n) => format!("({})", -i32::from(n) - 1),
+                Quality::Minor => "m".to_string(),
+                Quality::Perfect => String::new(),
+                Quality::Major => "j".to_string(),
+                Quality::Augmented(1) => "a".to_string(),
+                Quality::Augmented(n) => format!("({})", i32::from(n) + 1),
+            };
+            assert_eq!(
+                format!("{quality}{}", interval.number()),
+                interval.to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn split_and_invert() {
+        for s in ["1", "a1", "m2", "j7", "8", "j10", "a11", "15", "-m3", "-j9"] {
+            let interval = Interval::from_str(s).unwrap();
+            let (octaves, simple) = interval.split_octaves();
+            assert_eq!(Interval::new(12 * octaves, 7 * octaves) + simple, interval);
+            assert!(simple.diatonic.abs() < 7);
+            assert_eq!(interval.inversion().inversion().split_octaves().1, simple);
+        }
+        assert_eq!(
+            Interval::from_str("d5").unwrap().inversion(),
+            Interval::AUG_FOURTH
+        );
+        assert_eq!(
+            Interval::from_str("15").unwrap().inversion(),
+            Interval::UNISON
+        );
+    }
+
+    #[test]
+    fn enharmonic() {
+        let a2 = Interval::from_str("a2").unwrap();
+        assert!(a2.is_enharmonic(&Interval::MIN_THIRD));
+        assert!(!a2.is_enharmonic(&Interval::MAJ_THIRD));
+        assert!(Interval::AUG_FOURTH.is_enharmonic(&Interval::DIM_FIFTH));
+    }
+
+    #[test]
+    fn names() {
+        let name = |s: &str| Interval::from_str(s).unwrap().long_name();
+        assert_eq!(name("1"), "perfect unison");
+        assert_eq!(name("8"), "perfect octave");
+        assert_eq!(name("j7"), "major seventh");
+        assert_eq!(name("(5)5"), "4 times augmented fifth");
+        assert_eq!(name("a1"), "augmented unison");
+        assert_eq!(name("j21"), "major 21st");
+        assert_eq!(name("22"), "perfect 22nd");
+        assert_eq!(name("m112"), "minor 112th");
+    }
+}
@@ -0,0 +1,280 @@
+use std::{fmt, str::FromStr};
+
+use crate::harmony::{Accidental, ParsePitchError, Pitch, PitchName};
+
+/// The syntonic comma 81/80 in cents
+const SYNTONIC_COMMA: f64 = 21.506_289_596_014;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A pitch with microtonal alterations: quarter tones and syntonic comma arrows.
+///
+/// A micro pitch is a [`Pitch`] raised or lowered by at most one quarter tone and by any number
+/// of syntonic commas, as notated by Stein-Zimmermann quarter tone accidentals and by the arrows
+/// of the Helmholtz-Ellis just intonation notation (HEJI).
+/// The quarter tone always points in the direction of the accidental, so a three quarter tone
+/// sharp is stored as a sharp raised by a quarter tone.
+///
+/// # FromStr and Display implementation
+///
+/// A micro pitch is written as a pitch followed by `"+"` for a quarter tone sharp or `"d"` for a
+/// quarter tone flat and by any number of arrows `"↑"` or `"↓"` before the octave.
+/// Since `"+"` marks a quarter tone, double sharps have to be written as `"##"`.
+/// ```
+/// # use music_types::harmony::{MicroPitch, Pitch, ParsePitchError};
+/// # use std::str::FromStr;
+/// let quarter_sharp = MicroPitch::from_str("C+4")?;
+/// assert_eq!(quarter_sharp.pitch(), Pitch::from_str("C4")?);
+/// assert_eq!(quarter_sharp.quarter_tone(), 1);
+///
+/// let three_quarter_flat = MicroPitch::from_str("Ebd4")?;
+/// assert_eq!(three_quarter_flat.pitch(), Pitch::from_str("Eb4")?);
+/// assert_eq!(three_quarter_flat.quarter_tone(), -1);
+///
+/// let lowered_third = MicroPitch::from_str("E↓4")?;
+/// assert_eq!(lowered_third.arrows(), -1);
+///
+/// assert_eq!(MicroPitch::from_str("F##↑↑3")?.to_string(), "F##↑↑3");
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub struct MicroPitch {
+    pitch: Pitch,
+    quarter_tone: i16,
+    arrows: i16,
+}
+
+impl MicroPitch {
+    /// creates a micro pitch by raising `pitch` by `quarter_tones` quarter tones and by
+    /// `arrows` syntonic commas
+    ///
+    /// The accidental of `pitch` absorbs whole semitones, so that at most one quarter tone remains.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{MicroPitch, ParsePitchError};
+    /// # use std::str::FromStr;
+    /// let pitch = MicroPitch::new(FromStr::from_str("C4")?, 3, 0);
+    /// assert_eq!(pitch, MicroPitch::from_str("C#+4")?);
+    /// let pitch = MicroPitch::new(FromStr::from_str("C#4")?, -1, 0);
+    /// assert_eq!(pitch, MicroPitch::from_str("C+4")?);
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn new(pitch: Pitch, quarter_tones: i16, arrows: i16) -> Self {
+        let quarter_tones = 2 * pitch.accidental().chromatic_shift() + quarter_tones;
+        Self {
+            pitch: pitch.with_accidental(Accidental::new(quarter_tones / 2)),
+            quarter_tone: quarter_tones % 2,
+            arrows,
+        }
+    }
+
+    /// returns the pitch without microtonal alterations
+    pub fn pitch(&self) -> Pitch {
+        self.pitch
+    }
+
+    /// returns the quarter tone alteration, which is -1, 0 or 1
+    pub fn quarter_tone(&self) -> i16 {
+        self.quarter_tone
+    }
+
+    /// returns the number of syntonic commas by which the pitch is raised
+    pub fn arrows(&self) -> i16 {
+        self.arrows
+    }
+
+    /// returns the distance from C4 in cents, when tuned as an alteration of twelve tone equal
+    /// temperament
+    pub fn to_cents(&self) -> f64 {
+        let quarter_tones = 2 * self.pitch.chromatic + self.quarter_tone;
+        f64::from(self.arrows).mul_add(SYNTONIC_COMMA, f64::from(quarter_tones) * 50.0)
+    }
+
+    /// Converts the pitch to a frequency using the standard tuning A4 = 440Hz
+    pub fn to_frequency(&self) -> f32 {
+        self.to_frequency_tuning(440.0)
+    }
+
+    /// Converts the pitch to a frequency using the given tuning for A4
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{MicroPitch, ParsePitchError};
+    /// # use std::str::FromStr;
+    /// let a_quarter_sharp = MicroPitch::from_str("A+4")?.to_frequency_tuning(440.0);
+    /// assert!((a_quarter_sharp - 452.89).abs() < 0.01);
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn to_frequency_tuning(&self, a_4: f32) -> f32 {
+        #[allow(clippy::cast_possible_truncation)]
+        let octaves = ((self.to_cents() - 900.0) / 1200.0) as f32;
+        a_4 * octaves.exp2()
+    }
+}
+
+impl From<Pitch> for MicroPitch {
+    fn from(pitch: Pitch) -> Self {
+        Self::new(pitch, 0, 0)
+    }
+}
+
+#[cfg(feature = "smufl")]
+impl MicroPitch {
+    /// returns the corresponding smufl glyph
+    ///
+    /// Quarter tones are mapped to Stein-Zimmermann accidentals and arrows to the combined
+    /// accidentals of the Helmholtz-Ellis notation, which exist up to three arrows and double
+    /// sharps and flats. Quarter tones combined with arrows have no glyph.
+    pub fn to_smufl(&self) -> Option<smufl::Glyph> {
+        use smufl::Glyph::*;
+
+        match (
+            self.pitch.accidental().chromatic_shift(),
+            self.quarter_tone,
+            self.arrows,
+        ) {
+            (_, 0, 0) => self.pitch.accidental().to_smufl(),
+            (0, 1, 0) => Some(AccidentalQuarterToneSharpStein),
+            (1, 1, 0) => Some(AccidentalThreeQuarterTonesSharpStein),
+            (0, -1, 0) => Some(AccidentalQuarterToneFlatStein),
+            (-1, -1, 0) => Some(AccidentalThreeQuarterTonesFlatZimmermann),
+            (-2, 0, -3) => Some(AccidentalDoubleFlatThreeArrowsDown),
+            (-2, 0, -2) => Some(AccidentalDoubleFlatTwoArrowsDown),
+            (-2, 0, -1) => Some(AccidentalDoubleFlatOneArrowDown),
+            (-2, 0, 1) => Some(AccidentalDoubleFlatOneArrowUp),
+            (-2, 0, 2) => Some(AccidentalDoubleFlatTwoArrowsUp),
+            (-2, 0, 3) => Some(AccidentalDoubleFlatThreeArrowsUp),
+            (-1, 0, -3) => Some(AccidentalFlatThreeArrowsDown),
+            (-1, 0, -2) => Some(AccidentalFlatTwoArrowsDown),
+            (-1, 0, -1) => Some(AccidentalFlatOneArrowDown),
+            (-1, 0, 1) => Some(AccidentalFlatOneArrowUp),
+            (-1, 0, 2) => Some(AccidentalFlatTwoArrowsUp),
+            (-1, 0, 3) => Some(AccidentalFlatThreeArrowsUp),
+            (0, 0, -3) => Some(AccidentalNaturalThreeArrowsDown),
+            (0, 0, -2) => Some(AccidentalNaturalTwoArrowsDown),
+            (0, 0, -1) => Some(AccidentalNaturalOneArrowDown),
+            (0, 0, 1) => Some(AccidentalNaturalOneArrowUp),
+            (0, 0, 2) => Some(AccidentalNaturalTwoArrowsUp),
+            (0, 0, 3) => Some(AccidentalNaturalThreeArrowsUp),
+            (1, 0, -3) => Some(AccidentalSharpThreeArrowsDown),
+            (1, 0, -2) => Some(AccidentalSharpTwoArrowsDown),
+            (1, 0, -1) => Some(AccidentalSharpOneArrowDown),
+            (1, 0, 1) => Some(AccidentalSharpOneArrowUp),
+            (1, 0, 2) => Some(AccidentalSharpTwoArrowsUp),
+            (1, 0, 3) => Some(AccidentalSharpThreeArrowsUp),
+            (2, 0, -3) => Some(AccidentalDoubleSharpThreeArrowsDown),
+            (2, 0, -2) => Some(AccidentalDoubleSharpTwoArrowsDown),
+            (2, 0, -1) => Some(AccidentalDoubleSharpOneArrowDown),
+            (2, 0, 1) => Some(AccidentalDoubleSharpOneArrowUp),
+            (2, 0, 2) => Some(AccidentalDoubleSharpTwoArrowsUp),
+            (2, 0, 3) => Some(AccidentalDoubleSharpThreeArrowsUp),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for MicroPitch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, accidental, octave) = self.pitch.decompose();
+        write!(f, "{name}")?;
+        // `+` would be read as a quarter tone sharp
+        if accidental == Accidental::DOUBLE_SHARP {
+            write!(f, "##")?;
+        } else {
+            write!(f, "{accidental}")?;
+        }
+        match self.quarter_tone {
+            1 => write!(f, "+")?,
+            -1 => write!(f, "d")?,
+            _ => (),
+        }
+        let arrow = if self.arrows > 0 { "↑" } else { "↓" };
+        write!(
+            f,
+            "{}{octave}",
+            arrow.repeat(self.arrows.unsigned_abs().into())
+        )
+    }
+}
+
+impl FromStr for MicroPitch {
+    type Err = ParsePitchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (class, octave) = Pitch::split_octave(s)?;
+        let mut chars = class.chars();
+        let name = chars
+            .next()
+            .and_then(PitchName::new)
+            .ok_or_else(|| ParsePitchError::InvalidPitchName(s.to_string()))?;
+
+        let rest = chars.as_str();
+        let without_arrows = rest.trim_end_matches(['↑', '↓']);
+        let arrows = rest[without_arrows.len()..]
+            .chars()
+            .map(|c| if c == '↑' { 1 } else { -1 })
+            .sum();
+        let (accidental, quarter_tones) = match without_arrows.chars().last() {
+            Some('+') => (&without_arrows[..without_arrows.len() - 1], 1),
+            Some('d') => (&without_arrows[..without_arrows.len() - 1], -1),
+            _ => (without_arrows, 0),
+        };
+
+        let pitch = Pitch::compose(name, Accidental::from_str(accidental)?, octave);
+        Ok(Self::new(pitch, quarter_tones, arrows))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        for s in [
+            "C4",
+            "Cd4",
+            "C+4",
+            "C#+4",
+            "Cbd4",
+            "E↓4",
+            "Bb↑↑2",
+            "G##5",
+            "F&d-1",
+        ] {
+            assert_eq!(MicroPitch::from_str(s).unwrap().to_string(), s);
+        }
+        assert_eq!(
+            MicroPitch::from_str("C+4").unwrap(),
+            MicroPitch::new(Pitch::from_str("C4").unwrap(), 1, 0)
+        );
+        assert!(MicroPitch::from_str("H+4").is_err());
+        assert!(MicroPitch::from_str("C+").is_err());
+        assert!(MicroPitch::from_str("C↑#4").is_err());
+    }
+
+    #[test]
+    fn cents() {
+        let cents = |s: &str| MicroPitch::from_str(s).unwrap().to_cents();
+        assert!((cents("C+4") - 50.0).abs() < 1e-9);
+        assert!((cents("Dbd4") - 50.0).abs() < 1e-9);
+        assert!((cents("C#+3") + 1050.0).abs() < 1e-9);
+        assert!((cents("E↓4") - 378.494).abs() < 1e-3);
+    }
+
+    #[cfg(feature = "smufl")]
+    #[test]
+    fn smufl() {
+        use smufl::Glyph;
+
+        let glyph = |s: &str| MicroPitch::from_str(s).unwrap().to_smufl();
+        assert_eq!(glyph("Eb4"), Some(Glyph::AccidentalFlat));
+        assert_eq!(glyph("Ed4"), Some(Glyph::AccidentalQuarterToneFlatStein));
+        assert_eq!(
+            glyph("Ebd4"),
+            Some(Glyph::AccidentalThreeQuarterTonesFlatZimmermann)
+        );
+        assert_eq!(glyph("F#↑4"), Some(Glyph::AccidentalSharpOneArrowUp));
+        assert_eq!(glyph("F#+↑4"), None);
+    }
+}
@@ -0,0 +1,477 @@
+use std::str::FromStr;
+
+use crate::harmony::{Accidental, ChromaticPitch, ParsePitchError, Pitch, PitchName};
+
+/// the fixed do syllables from C to B
+const SYLLABLES: [&str; 7] = ["Do", "Re", "Mi", "Fa", "Sol", "La", "Si"];
+
+/// the syllables recognized when parsing solfège, lowercase
+const PARSED_SYLLABLES: [(&str, char); 9] = [
+    ("do", 'C'),
+    ("ut", 'C'),
+    ("re", 'D'),
+    ("ré", 'D'),
+    ("mi", 'E'),
+    ("fa", 'F'),
+    ("sol", 'G'),
+    ("la", 'A'),
+    ("si", 'B'),
+];
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A system of names for pitch classes
+///
+/// # Examples
+/// ```
+/// # use music_types::harmony::{NamingSystem, Pitch, ParsePitchError};
+/// # use std::str::FromStr;
+/// let b_flat = Pitch::class_from_str("Bb")?;
+/// assert_eq!(NamingSystem::English.name(b_flat), "Bb");
+/// assert_eq!(NamingSystem::German.name(b_flat), "B");
+/// assert_eq!(NamingSystem::Solfege.name(b_flat), "Sib");
+/// assert_eq!(NamingSystem::Dutch.name(b_flat), "bes");
+/// assert_eq!(NamingSystem::Nordic.name(Pitch::class_from_str("Ab")?), "Ass");
+/// assert_eq!(NamingSystem::German.parse_class("Fis")?, Pitch::class_from_str("F#")?);
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub enum NamingSystem {
+    /// English letters with the accidentals of [`Accidental`], as in the implementations of
+    /// [`Display`][`std::fmt::Display`] and [`FromStr`] for [`Pitch`]
+    #[default]
+    English,
+    /// German names, where B is called H and Bb is called B, with the suffix `is` for sharps
+    /// and `es` for flats, like Fis, Es, As and Heses
+    German,
+    /// Fixed do solfège with the syllables Do, Re, Mi, Fa, Sol, La and Si followed by `#` or `b`
+    ///
+    /// When parsing, Ut and Ré are accepted as well and the case is ignored.
+    Solfege,
+    /// Dutch names as used by `LilyPond`, in lowercase with the suffix `is` for sharps and `es`
+    /// for flats, like fis, es and bes
+    Dutch,
+    /// Scandinavian names, like German names but with the suffixes `iss` and `ess`, like Fiss,
+    /// Ess, Ass and Bess
+    Nordic,
+}
+
+impl NamingSystem {
+    /// the suffixes for a sharp and a flat in systems which use syllables for accidentals
+    fn suffixes(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Self::English | Self::Solfege => None,
+            Self::German | Self::Dutch => Some(("is", "es")),
+            Self::Nordic => Some(("iss", "ess")),
+        }
+    }
+
+    /// Returns the name of the pitch class of `pitch` in this system, without the octave.
+    pub fn name(self, pitch: Pitch) -> String {
+        self.class_name(pitch.pitch_name(), pitch.accidental())
+    }
+
+    /// Returns the name of the pitch class given by `name` and `accidental` in this system.
+    pub fn class_name(self, name: PitchName, accidental: Accidental) -> String {
+        let shift = accidental.chromatic_shift();
+        let count = usize::from(shift.unsigned_abs());
+        let Some((sharp, flat)) = self.suffixes() else {
+            return match self {
+                Self::Solfege => {
+                    let symbol = if shift > 0 { "#" } else { "b" };
+                    #[allow(clippy::cast_sign_loss)]
+                    let syllable = SYLLABLES[name.to_diatonic_steps() as usize];
+                    format!("{syllable}{}", symbol.repeat(count))
+                }
+                _ => format!("{name}{accidental}"),
+            };
+        };
+
+        let letter = name.as_char();
+        let name = match (letter, shift, self) {
+            ('B', 1.., Self::German | Self::Nordic) => format!("H{}", sharp.repeat(count)),
+            ('B', 0, Self::German | Self::Nordic) => "H".to_string(),
+            ('B', -1, Self::German | Self::Nordic) => "B".to_string(),
+            ('B', ..-1, Self::German) => format!("H{}", flat.repeat(count)),
+            ('B', ..-1, Self::Nordic) => format!("B{}", flat.repeat(count - 1)),
+            ('E' | 'A', ..0, _) => format!("{letter}{}{}", &flat[1..], flat.repeat(count - 1)),
+            (_, 0.., _) => format!("{letter}{}", sharp.repeat(count)),
+            (_, ..0, _) => format!("{letter}{}", flat.repeat(count)),
+        };
+        if self == Self::Dutch {
+            name.to_lowercase()
+        } else {
+            name
+        }
+    }
+
+    /// Parses a pitch class in this system and returns its name and accidental.
+    ///
+    /// # Errors
+    /// Returns an error if the string does not start with a name of this system or if the
+    /// rest of the string is not an accidental.
+    pub fn parse_name(self, s: &str) -> Result<(PitchName, Accidental), ParsePitchError> {
+        let invalid_name = || ParsePitchError::InvalidPitchName(s.to_string());
+        let Some((sharp, flat)) = self.suffixes() else {
+            if self == Self::English {
+                return Pitch::parse_class(s);
+            }
+            let (syllable, letter) = PARSED_SYLLABLES
+                .iter()
+                .find(|(syllable, _)| {
+                    s.get(..syllable.len())
+                        .is_some_and(|prefix| prefix.to_lowercase() == *syllable)
+                })
+                .ok_or_else(invalid_name)?;
+            let name = PitchName::new(*letter).ok_or_else(invalid_name)?;
+            return Ok((name, Accidental::from_str(&s[syllable.len()..])?));
+        };
+
+        let mut chars = s.chars();
+        let letter = chars.next().ok_or_else(invalid_name)?.to_ascii_uppercase();
+        let (letter, mut shift) = match letter {
+            'H' if self != Self::Dutch => ('B', 0),
+            'B' if self != Self::Dutch => ('B', -1),
+            letter => (letter, 0),
+        };
+        let name = PitchName::new(letter).ok_or_else(invalid_name)?;
+        let mut rest = chars.as_str();
+        // Es and As drop the vowel of the first flat
+        if matches!(letter, 'E' | 'A')
+            && let Some(r) = rest.strip_prefix(&flat[1..])
+        {
+            shift -= 1;
+            rest = r;
+        }
+        let repetitions = |unit: &str| {
+            let count = rest.len() / unit.len();
+            (rest == unit.repeat(count)).then_some(count)
+        };
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        match (repetitions(sharp), repetitions(flat)) {
+            (Some(count), _) if shift == 0 => shift += count as i16,
+            (_, Some(count)) => shift -= count as i16,
+            _ => return Err(ParsePitchError::InvalidAccidental(rest.to_string())),
+        }
+        Ok((name, Accidental::new(shift)))
+    }
+
+    /// Parses a pitch class in this system, see [`Pitch::class_from_str`].
+    ///
+    /// # Errors
+    /// See [`parse_name`][`Self::parse_name`].
+    pub fn parse_class(self, s: &str) -> Result<Pitch, ParsePitchError> {
+        let (name, accidental) = self.parse_name(s)?;
+        Ok(Pitch::from_pitch_class(name, accidental))
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A notation for the octave of a pitch
+pub enum OctaveNotation {
+    /// Scientific pitch notation, the octave number follows the name, like C4 for middle C
+    #[default]
+    Scientific,
+    /// Helmholtz notation, where the octave below middle C is written in lowercase like `c`,
+    /// higher octaves add primes like `c'` for middle C, and the octave below `c` is written in
+    /// uppercase like `C` with commas for lower octaves like `C,`
+    Helmholtz,
+    /// The octave marks of ABC notation, where the octave from middle C is written in
+    /// uppercase like `C`, the octave above in lowercase like `c` and further octaves add
+    /// primes like `c'` or commas like `C,`
+    Abc,
+    /// The MIDI note number, like 60 for middle C, which does not preserve the spelling
+    ///
+    /// Pitches outside of the MIDI range are numbered as if it continued.
+    Midi,
+}
+
+impl OctaveNotation {
+    /// the octave written as the name in lowercase without marks, if the notation uses marks
+    fn lowercase_octave(self) -> Option<i16> {
+        match self {
+            Self::Scientific | Self::Midi => None,
+            Self::Helmholtz => Some(3),
+            Self::Abc => Some(5),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Formats and parses pitches in a selectable [`NamingSystem`] and [`OctaveNotation`]
+///
+/// By default, pitches are written with English names in scientific pitch notation, as in the
+/// implementations of [`Display`][`std::fmt::Display`] and [`FromStr`] for [`Pitch`].
+///
+/// # Examples
+/// ```
+/// # use music_types::harmony::{NamingSystem, OctaveNotation, Pitch, PitchFormatter, ParsePitchError};
+/// # use std::str::FromStr;
+/// let german = PitchFormatter::new(NamingSystem::German);
+/// assert_eq!(german.format(Pitch::from_str("Eb4")?), "Es4");
+/// assert_eq!(german.parse("H3")?, Pitch::from_str("B3")?);
+///
+/// let solfege = PitchFormatter::new(NamingSystem::Solfege);
+/// assert_eq!(solfege.format(Pitch::from_str("G#5")?), "Sol#5");
+/// assert_eq!(solfege.parse("ré4")?, Pitch::from_str("D4")?);
+///
+/// let helmholtz = german.with_octaves(OctaveNotation::Helmholtz);
+/// assert_eq!(helmholtz.format(Pitch::from_str("F#5")?), "fis''");
+/// assert_eq!(helmholtz.parse("B,")?, Pitch::from_str("Bb1")?);
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub struct PitchFormatter {
+    names: NamingSystem,
+    octaves: OctaveNotation,
+}
+
+impl PitchFormatter {
+    /// creates a formatter using the given naming system and scientific pitch notation
+    pub fn new(names: NamingSystem) -> Self {
+        Self {
+            names,
+            octaves: OctaveNotation::default(),
+        }
+    }
+
+    /// sets the octave notation
+    pub fn with_octaves(mut self, octaves: OctaveNotation) -> Self {
+        self.octaves = octaves;
+        self
+    }
+
+    /// returns the naming system
+    pub fn names(&self) -> NamingSystem {
+        self.names
+    }
+
+    /// returns the octave notation
+    pub fn octaves(&self) -> OctaveNotation {
+        self.octaves
+    }
+
+    /// formats the pitch
+    pub fn format(&self, pitch: Pitch) -> String {
+        let name = self.names.name(pitch);
+        let octave = pitch.octave();
+        let Some(lowercase) = self.octaves.lowercase_octave() else {
+            return match self.octaves {
+                // middle C is MIDI note 60
+                OctaveNotation::Midi => (i32::from(pitch.chromatic) + 60).to_string(),
+                _ => format!("{name}{octave}"),
+            };
+        };
+        #[allow(clippy::cast_sign_loss)]
+        if octave >= lowercase {
+            let primes = "'".repeat((octave - lowercase) as usize);
+            format!("{}{primes}", name.to_lowercase())
+        } else {
+            let commas = ",".repeat((lowercase - 1 - octave) as usize);
+            let mut chars = name.chars();
+            let first = chars.next().map(char::to_uppercase);
+            format!(
+                "{}{}{commas}",
+                first.into_iter().flatten().collect::<String>(),
+                chars.as_str()
+            )
+        }
+    }
+
+    /// Parses a pitch.
+    ///
+    /// Helmholtz and ABC notation also accept the prime `′` in place of the apostrophe.
+    ///
+    /// # Errors
+    /// Returns an error if the octave is missing or invalid, or if the pitch class is invalid
+    /// in the naming system.
+    pub fn parse(&self, s: &str) -> Result<Pitch, ParsePitchError> {
+        let Some(lowercase) = self.octaves.lowercase_octave() else {
+            if self.octaves == OctaveNotation::Midi {
+                let number = i16::from_str(s)
+                    .ok()
+                    .and_then(|number| number.checked_sub(60))
+                    .ok_or_else(|| ParsePitchError::InvalidOctave(s.to_string()))?;
+                return Ok(ChromaticPitch::new(number).to_pitch());
+            }
+            let (class, octave) = Pitch::split_octave(s)?;
+            let (name, accidental) = self.names.parse_name(class)?;
+            return Ok(Pitch::compose(name, accidental, octave));
+        };
+
+        let class = s.trim_end_matches(['\'', '′', ',']);
+        let marks = &s[class.len()..];
+        let invalid_octave = || ParsePitchError::InvalidOctave(marks.to_string());
+        let mut chars = class.chars();
+        let first = chars
+            .next()
+            .ok_or_else(|| ParsePitchError::InvalidPitchName(s.to_string()))?;
+        let commas = marks.chars().filter(|c| *c == ',').count();
+        let primes = marks.chars().count() - commas;
+        let (commas, primes) = i16::try_from(commas)
+            .ok()
+            .zip(i16::try_from(primes).ok())
+            .ok_or_else(invalid_octave)?;
+        let octave = if first.is_lowercase() && commas == 0 {
+            lowercase + primes
+        } else if first.is_uppercase() && primes == 0 {
+            lowercase - 1 - commas
+        } else {
+            return Err(invalid_octave());
+        };
+        let class: String = first.to_uppercase().chain(chars).collect();
+        let (name, accidental) = self.names.parse_name(&class)?;
+        Ok(Pitch::compose(name, accidental, octave))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SYSTEMS: [NamingSystem; 5] = [
+        NamingSystem::English,
+        NamingSystem::German,
+        NamingSystem::Solfege,
+        NamingSystem::Dutch,
+        NamingSystem::Nordic,
+    ];
+
+    #[test]
+    fn names() {
+        let names = |system: NamingSystem, pitches: &[&str]| -> Vec<String> {
+            pitches
+                .iter()
+                .map(|s| system.name(Pitch::class_from_str(s).unwrap()))
+                .collect()
+        };
+        let pitches = ["C", "F#", "Eb", "Ab", "B", "Bb", "Bbb", "B#", "Ebb", "G##"];
+        assert_eq!(
+            names(NamingSystem::German, &pitches),
+            [
+                "C", "Fis", "Es", "As", "H", "B", "Heses", "His", "Eses", "Gisis"
+            ]
+        );
+        assert_eq!(
+            names(NamingSystem::Dutch, &pitches),
+            [
+                "c", "fis", "es", "as", "b", "bes", "beses", "bis", "eses", "gisis"
+            ]
+        );
+        assert_eq!(
+            names(NamingSystem::Nordic, &pitches),
+            [
+                "C", "Fiss", "Ess", "Ass", "H", "B", "Bess", "Hiss", "Essess", "Gississ"
+            ]
+        );
+        assert_eq!(
+            names(NamingSystem::Solfege, &pitches),
+            [
+                "Do", "Fa#", "Mib", "Lab", "Si", "Sib", "Sibb", "Si#", "Mibb", "Sol##"
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        for system in SYSTEMS {
+            let formatter = PitchFormatter::new(system);
+            for diatonic in -8..=8 {
+                for shift in -3..=3 {
+                    let pitch = Pitch::new(diatonic, 0).with_accidental(Accidental::new(shift));
+                    let text = formatter.format(pitch);
+                    assert_eq!(formatter.parse(&text).unwrap(), pitch, "{text}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn alternatives() {
+        let parse = |system: NamingSystem, s: &str| system.parse_class(s).unwrap();
+        let class = |s: &str| Pitch::class_from_str(s).unwrap();
+        assert_eq!(parse(NamingSystem::Dutch, "ees"), class("Eb"));
+        assert_eq!(parse(NamingSystem::Dutch, "aeses"), class("Abb"));
+        assert_eq!(parse(NamingSystem::German, "fis"), class("F#"));
+        assert_eq!(parse(NamingSystem::Solfege, "UT#"), class("C#"));
+        assert_eq!(parse(NamingSystem::Solfege, "Ré"), class("D"));
+        assert!(NamingSystem::German.parse_class("Bis").is_err());
+        assert!(NamingSystem::German.parse_class("Fesis").is_err());
+        assert!(NamingSystem::Nordic.parse_class("Fises").is_err());
+        assert!(NamingSystem::Solfege.parse_class("C").is_err());
+    }
+
+    #[test]
+    fn octave_notations() {
+        let english = PitchFormatter::default();
+        let format = |octaves: OctaveNotation, pitches: &[&str]| -> Vec<String> {
+            pitches
+                .iter()
+                .map(|s| {
+                    english
+                        .with_octaves(octaves)
+                        .format(Pitch::from_str(s).unwrap())
+                })
+                .collect()
+        };
+        let pitches = ["C0", "Bb1", "C2", "F#3", "C4", "Eb5", "B6"];
+        assert_eq!(
+            format(OctaveNotation::Helmholtz, &pitches),
+            ["C,,", "Bb,", "C", "f#", "c'", "eb''", "b'''"]
+        );
+        assert_eq!(
+            format(OctaveNotation::Abc, &pitches),
+            ["C,,,,", "Bb,,,", "C,,", "F#,", "C", "eb", "b'"]
+        );
+        assert_eq!(
+            format(OctaveNotation::Midi, &pitches),
+            ["12", "34", "36", "54", "60", "75", "95"]
+        );
+        let helmholtz = english.with_octaves(OctaveNotation::Helmholtz);
+        assert_eq!(
+            helmholtz.parse("c′′").unwrap(),
+            Pitch::from_str("C5").unwrap()
+        );
+        assert!(helmholtz.parse("c,").is_err());
+        assert!(helmholtz.parse("C'").is_err());
+        assert!(helmholtz.parse("'").is_err());
+        let midi = english.with_octaves(OctaveNotation::Midi);
+        assert_eq!(midi.parse("61").unwrap(), Pitch::from_str("C#4").unwrap());
+        assert!(midi.parse("C4").is_err());
+        assert!(matches!(
+            midi.parse("-32768"),
+            Err(ParsePitchError::InvalidOctave(s)) if s == "-32768"
+        ));
+        assert_eq!(midi.format(Pitch::new(0, i16::MAX)), "32827");
+    }
+
+    #[test]
+    fn octave_round_trip() {
+        for system in SYSTEMS {
+            for octaves in [
+                OctaveNotation::Scientific,
+                OctaveNotation::Helmholtz,
+                OctaveNotation::Abc,
+            ] {
+                let formatter = PitchFormatter::new(system).with_octaves(octaves);
+                for diatonic in 0..7 {
+                    for shift in -2..=2 {
+                        for octave in -1..=9 {
+                            let name = PitchName::from_diatonic_steps(diatonic);
+                            let pitch = Pitch::compose(name, Accidental::new(shift), octave);
+                            let text = formatter.format(pitch);
+                            assert_eq!(formatter.parse(&text).unwrap(), pitch, "{text}");
+                        }
+                    }
+                }
+            }
+            let formatter = PitchFormatter::new(system).with_octaves(OctaveNotation::Midi);
+            for number in 0..=127 {
+                let pitch = ChromaticPitch::from_midi_pitch(number).to_pitch();
+                let text = formatter.format(pitch);
+                assert_eq!(text, number.to_string());
+                assert_eq!(formatter.parse(&text).unwrap(), pitch);
+            }
+        }
+    }
+}
@@ -3,7 +3,10 @@ mod parse;
 
 pub use parse::ParsePitchError;
 
-use crate::div_remainder;
+use crate::{
+    div_remainder,
+    tuning::{EqualTemperament, Tuning},
+};
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -47,6 +50,11 @@ impl Accidental {
         Self(chromatic_shift)
     }
 
+    /// Returns the chromatic shift of the accidental
+    pub const fn chromatic_shift(&self) -> i16 {
+        self.0
+    }
+
     /// Converts the accidental to the utf-8 aequivalent, if it exists.
     ///
     /// Note that '♭', '♮' and '♯' are in the unicode block for miscellaneous symbols (U+2600–U+26FF),
@@ -407,6 +415,23 @@ impl Pitch {
         self.to_chromatic().to_frequency_tuning(a_4)
     }
 
+    /// Converts the pitch to a frequency using the given tuning
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::{harmony::{Pitch, ParsePitchError}, tuning::RegularTemperament};
+    /// # use std::str::FromStr;
+    /// // G# and Ab are different pitches in meantone
+    /// let meantone = RegularTemperament::quarter_comma_meantone(440.0);
+    /// let g_sharp = Pitch::from_str("G#4")?.to_frequency_with(&meantone);
+    /// let a_flat = Pitch::from_str("Ab4")?.to_frequency_with(&meantone);
+    /// assert!(g_sharp < a_flat);
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn to_frequency_with(&self, tuning: &(impl Tuning + ?Sized)) -> f64 {
+        tuning.frequency(*self)
+    }
+
     /// Converts to the chromatic pitch
     pub fn to_chromatic(&self) -> ChromaticPitch {
         (*self).into()
@@ -445,6 +470,36 @@ impl ChromaticPitch {
         a_4 * 2.0_f32.powf((self.0 - 9) as f32 / 12.0)
     }
 
+    /// Converts the pitch to a frequency using the given tuning
+    pub fn to_frequency_with(&self, tuning: &(impl Tuning + ?Sized)) -> f64 {
+        tuning.chromatic_frequency(*self)
+    }
+
+    /// Returns the nearest pitch to `frequency` using the standard tuning A4 = 440Hz,
+    /// together with the deviation from that pitch in cents
+    pub fn from_frequency(frequency: f32) -> (Self, f32) {
+        Self::from_frequency_tuning(frequency, 440.0)
+    }
+
+    /// Returns the nearest pitch to `frequency` using the given tuning for A4,
+    /// together with the deviation from that pitch in cents
+    ///
+    /// The deviation is in `-50.0..=50.0`, a frequency exactly between two pitches is assigned to
+    /// the lower pitch. See [`Tuning::nearest_chromatic`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::ChromaticPitch;
+    /// let (pitch, cents) = ChromaticPitch::from_frequency_tuning(445.0, 440.0);
+    /// assert_eq!(pitch, ChromaticPitch::new(9));
+    /// assert!((cents - 19.56).abs() < 0.01);
+    /// ```
+    pub fn from_frequency_tuning(frequency: f32, a_4: f32) -> (Self, f32) {
+        let (pitch, cents) = EqualTemperament::new(a_4.into()).nearest_chromatic(frequency.into());
+        #[allow(clippy::cast_possible_truncation)]
+        (pitch, cents as f32)
+    }
+
     /// Converts the chromatic pitch to a Pitch
     /// choosing a reasonable diatonic representation.
     pub fn to_pitch(&self) -> Pitch {
@@ -466,7 +521,7 @@ impl ChromaticPitch {
         let (mut octave, chromatic) = div_remainder(self.0, 12);
         if chromatic - name.to_chromatic_steps() > 6 {
             octave += 1
-        } else if chromatic - name.to_chromatic_steps() > 6 {
+        } else if chromatic - name.to_chromatic_steps() < -6 {
             octave -= 1
         }
         Pitch {
@@ -525,6 +580,27 @@ mod test {
             Pitch::from_str("Cb4").unwrap(),
             pitch.to_pitch_named(PitchName(b'C'))
         );
+
+        let pitch = ChromaticPitch::new(12);
+        assert_eq!(
+            Pitch::from_str("B#4").unwrap(),
+            pitch.to_pitch_named(PitchName(b'B'))
+        );
+    }
+
+    #[test]
+    fn from_frequency() {
+        for steps in -48..48 {
+            let pitch = ChromaticPitch::new(steps);
+            let (nearest, cents) = ChromaticPitch::from_frequency(pitch.to_frequency());
+            assert_eq!(nearest, pitch);
+            assert!(cents.abs() < 0.01);
+        }
+        // a quarter tone above A4 is assigned to A4
+        let quarter_sharp = 440.0 * 2.0_f32.powf(1.0 / 24.0);
+        let (nearest, cents) = ChromaticPitch::from_frequency(quarter_sharp);
+        assert_eq!(nearest, ChromaticPitch::new(9));
+        assert!((cents - 50.0).abs() < 0.01);
     }
 
     #[test]
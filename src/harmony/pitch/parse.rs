@@ -90,7 +90,7 @@ impl Pitch {
         Ok(Self::from_pitch_class(name, accidental))
     }
 
-    fn parse_class(s: &str) -> Result<(PitchName, Accidental), ParsePitchError> {
+    pub(crate) fn parse_class(s: &str) -> Result<(PitchName, Accidental), ParsePitchError> {
         let mut chars = s.chars();
         let pitch_name = PitchName::new(
             chars
@@ -102,28 +102,27 @@ impl Pitch {
     }
 }
 
-impl FromStr for Pitch {
-    type Err = ParsePitchError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut octave_index = s
-            .char_indices()
-            .rev()
-            .find(|&(_, c)| !c.is_ascii_digit())
-            .map(|(i, _)| i)
-            .ok_or(ParsePitchError::NoOctaveFound)?
-            + 1;
-        if s.chars()
-            .nth(octave_index - 1)
-            .ok_or(ParsePitchError::InvalidPitchName(s.to_string()))?
-            == '-'
-        {
-            octave_index -= 1;
+impl Pitch {
+    /// splits a pitch into the pitch class and the octave
+    pub(crate) fn split_octave(s: &str) -> Result<(&str, i16), ParsePitchError> {
+        let class = s.trim_end_matches(|c: char| c.is_ascii_digit());
+        if class.is_empty() {
+            return Err(ParsePitchError::NoOctaveFound);
         }
+        let octave_index = class.strip_suffix('-').unwrap_or(class).len();
         let octave_str = &s[octave_index..];
         let octave: i16 = FromStr::from_str(octave_str)
             .map_err(|_| ParsePitchError::InvalidOctave(octave_str.to_string()))?;
-        let (pitch_name, accidental) = Self::parse_class(&s[0..octave_index])?;
+        Ok((&s[0..octave_index], octave))
+    }
+}
+
+impl FromStr for Pitch {
+    type Err = ParsePitchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (class, octave) = Self::split_octave(s)?;
+        let (pitch_name, accidental) = Self::parse_class(class)?;
         Ok(Self::compose(pitch_name, accidental, octave))
     }
 }
@@ -187,4 +186,15 @@ mod test {
         assert!(Pitch::from_str("Ch").is_err());
         assert!(Pitch::from_str("c18").is_err());
     }
+
+    #[test]
+    fn parse_pitch_errors() {
+        let error = |s| Pitch::from_str(s).unwrap_err();
+        assert!(matches!(error("C"), ParsePitchError::InvalidOctave(s) if s.is_empty()));
+        assert!(matches!(error("C#-"), ParsePitchError::InvalidOctave(s) if s == "-"));
+        assert!(matches!(error("4"), ParsePitchError::NoOctaveFound));
+        assert!(matches!(error(""), ParsePitchError::NoOctaveFound));
+        assert!(matches!(error("-4"), ParsePitchError::InvalidPitchName(s) if s.is_empty()));
+        assert!(matches!(error("H4"), ParsePitchError::InvalidPitchName(s) if s == "H"));
+    }
 }
@@ -3,14 +3,20 @@ use std::ops::Add;
 
 use crate::{
     div_remainder,
-    harmony::{Accidental, ChromaticOctave, Interval, Pitch},
+    harmony::{
+        Accidental, ChromaticOctave, ChromaticPitch, Interval, Octave, Pitch,
+        chord::{Chord, RomanNumeral},
+    },
 };
 
+mod degree;
 mod display;
 mod parse;
 
 mod standard_scales;
 
+pub use degree::{MovableDo, ScaleDegree};
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// This struct represents a scale.
@@ -202,17 +208,286 @@ pub struct KeySignature(Vec<KeyAccidental>);
 impl KeySignature {
     /// creates the keysignature of the major scale with root `pitch`
     pub fn major(pitch: Pitch) -> Self {
+        Self::from_scale(pitch, &Scale::major())
+    }
+
+    /// creates the keysignature of the minor scale with root `pitch`
+    pub fn minor(pitch: Pitch) -> Self {
+        Self::major(pitch + Interval::MIN_THIRD)
+    }
+
+    /// creates the keysignature containing the accidentals of `scale` with root `pitch`
+    pub fn from_scale(pitch: Pitch, scale: &Scale) -> Self {
         // TODO: proper sorting of the accidentals so the are listed in canonical order
         let mut accs = Vec::new();
-        for p in Scale::major().iter_from_root(pitch).take(7) {
-            accs.push(KeyAccidental::new(p.staff_position(), p.accidental()))
+        for p in scale.iter_from_root(pitch).take(scale.0.len()) {
+            accs.push(KeyAccidental::new(p.staff_position(), p.accidental()));
         }
         Self(accs)
     }
 
-    /// creates the keysignature of the minor scale with root `pitch`
-    pub fn minor(pitch: Pitch) -> Self {
-        Self::major(pitch + Interval::MIN_THIRD)
+    /// returns the number of sharps in the key signature, where flats are counted negative.
+    ///
+    /// For the key signatures of major and minor scales this is the position of the key on the
+    /// circle of fifths.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{scale::KeySignature, Pitch, ParsePitchError};
+    /// assert_eq!(KeySignature::major(Pitch::class_from_str("D")?).fifths(), 2);
+    /// assert_eq!(KeySignature::minor(Pitch::class_from_str("C")?).fifths(), -3);
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn fifths(&self) -> i16 {
+        self.0
+            .iter()
+            .map(|acc| acc.accidental.chromatic_shift())
+            .sum()
+    }
+
+    /// returns the distance between the key signatures on the circle of fifths
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{scale::KeySignature, Pitch, ParsePitchError};
+    /// let e_major = KeySignature::major(Pitch::class_from_str("E")?);
+    /// let f_major = KeySignature::major(Pitch::class_from_str("F")?);
+    /// assert_eq!(e_major.distance(&f_major), 5);
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn distance(&self, other: &Self) -> i16 {
+        (self.fifths() - other.fifths()).abs()
+    }
+
+    /// Returns the altered pitch classes in the order they are written, sharps before flats.
+    ///
+    /// Sharps are ordered upwards and flats downwards on the circle of fifths.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{scale::KeySignature, Pitch, ParsePitchError};
+    /// let key = KeySignature::major(Pitch::class_from_str("Eb")?);
+    /// let names: Vec<_> = key.accidentals().iter().map(|p| p.to_string()).collect();
+    /// assert_eq!(names, ["Bb4", "Eb4", "Ab4"]);
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn accidentals(&self) -> Vec<Pitch> {
+        let mut pitches: Vec<_> = self
+            .0
+            .iter()
+            .filter(|acc| acc.accidental != Accidental::NATURAL)
+            .map(|acc| Pitch::new(acc.staffposition, 0).with_accidental(acc.accidental))
+            .collect();
+        // the position of the pitch class on the line of fifths
+        let fifths = |pitch: &Pitch| 7 * pitch.chromatic - 12 * pitch.diatonic;
+        pitches.sort_by_key(|pitch| {
+            let sharp = pitch.accidental().chromatic_shift() > 0;
+            (!sharp, if sharp { fifths(pitch) } else { -fifths(pitch) })
+        });
+        pitches
+    }
+
+    /// Transposes the key signature by `interval`, keeping the spelling of the interval.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{scale::KeySignature, Interval, Pitch, ParsePitchError};
+    /// let d_major = KeySignature::major(Pitch::class_from_str("D")?);
+    /// assert_eq!(d_major.transpose(Interval::MAJ_SECOND).fifths(), 4);
+    /// assert_eq!(d_major.transpose(-Interval::MAJ_THIRD).fifths(), -2);
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn transpose(&self, interval: Interval) -> Self {
+        let accs = self
+            .0
+            .iter()
+            .map(|acc| {
+                let pitch = Pitch::new(acc.staffposition, 0).with_accidental(acc.accidental);
+                let pitch = pitch + interval;
+                KeyAccidental::new(pitch.staff_position(), pitch.accidental())
+            })
+            .collect();
+        Self(accs)
+    }
+
+    /// Returns the enharmonically equivalent key signature with at most 6 sharps or flats.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{scale::KeySignature, Pitch, ParsePitchError};
+    /// let c_sharp = KeySignature::major(Pitch::class_from_str("C#")?);
+    /// assert_eq!(c_sharp.simplified().fifths(), -5);
+    /// let g_flat = KeySignature::major(Pitch::class_from_str("Gb")?);
+    /// assert_eq!(g_flat.simplified().fifths(), -6);
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn simplified(&self) -> Self {
+        // a diminished second is twelve fifths down
+        let diminished_second = Interval::new(0, 1);
+        match self.fifths() {
+            fifths if fifths > 6 => self.transpose(diminished_second).simplified(),
+            fifths if fifths < -6 => self.transpose(-diminished_second).simplified(),
+            _ => self.clone(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A key: a tonic together with a scale
+///
+/// The tonic is stored as a pitch class, see [`Pitch`].
+pub struct Key {
+    tonic: Pitch,
+    scale: Scale,
+}
+
+impl Key {
+    /// creates the key of `scale` on `tonic`
+    pub fn new(tonic: Pitch, scale: Scale) -> Self {
+        Self {
+            tonic: tonic % Octave,
+            scale,
+        }
+    }
+
+    /// creates the major key on `tonic`
+    pub fn major(tonic: Pitch) -> Self {
+        Self::new(tonic, Scale::major())
+    }
+
+    /// creates the minor key on `tonic`
+    pub fn minor(tonic: Pitch) -> Self {
+        Self::new(tonic, Scale::minor())
+    }
+
+    /// returns the tonic of the key as a pitch class
+    pub fn tonic(&self) -> Pitch {
+        self.tonic
+    }
+
+    /// returns the scale of the key
+    pub fn scale(&self) -> &Scale {
+        &self.scale
+    }
+
+    /// returns the key signature of the key
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{scale::Key, Pitch, ParsePitchError};
+    /// let key = Key::minor(Pitch::class_from_str("G")?);
+    /// assert_eq!(key.key_signature().fifths(), -2);
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn key_signature(&self) -> KeySignature {
+        KeySignature::from_scale(self.tonic, &self.scale)
+    }
+
+    /// returns the triads built on each degree of the scale by stacking every other note of the
+    /// scale, which are thirds for heptatonic scales
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{chord::Chord, scale::Key, Pitch, ParsePitchError};
+    /// # use std::str::FromStr;
+    /// let key = Key::minor(Pitch::class_from_str("A")?);
+    /// let triads = key.triads();
+    /// assert_eq!(triads[0], Chord::minor(Pitch::from_str("A4")?));
+    /// assert_eq!(triads[1], Chord::diminished(Pitch::from_str("B4")?));
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn triads(&self) -> Vec<Chord> {
+        let len = self.scale.0.len();
+        let pitches: Vec<_> = self
+            .scale
+            .iter_from_root(self.tonic)
+            .take(len + 5)
+            .collect();
+        (0..len)
+            .map(|i| {
+                let root = pitches[i];
+                Chord::new(root, vec![pitches[i + 2] - root, pitches[i + 4] - root])
+            })
+            .collect()
+    }
+
+    /// returns the roman numeral of a triad in this key
+    ///
+    /// Returns `None` if the root of the chord is not in the scale of the key or if the chord is
+    /// not a standard triad.
+    /// The root is compared by spelling, so in C major Gb is not V.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{chord::Chord, scale::Key, Pitch, ParsePitchError};
+    /// let key = Key::major(Pitch::class_from_str("F")?);
+    /// let numeral = key.roman_numeral(&Chord::minor(Pitch::class_from_str("G")?));
+    /// assert_eq!(numeral.unwrap().to_string(), "ii");
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn roman_numeral(&self, chord: &Chord) -> Option<RomanNumeral> {
+        let root = chord.root() % Octave;
+        let degree = self
+            .scale
+            .iter_from_root(self.tonic)
+            .take(self.scale.0.len().min(7))
+            .position(|p| p % Octave == root)?;
+        let degree = u8::try_from(degree + 1).ok()?;
+        Some(RomanNumeral::new(degree, chord.triad_quality()?))
+    }
+
+    /// spells a chromatic pitch in this key
+    ///
+    /// Pitches of the scale are spelled as in the scale. Other pitches are spelled by their
+    /// interval above the tonic: minor and major thirds, sixths and sevenths and the augmented
+    /// fourth, so the lowered third, sixth and seventh of a major key are spelled as flats.
+    /// A semitone above the tonic is an augmented unison in keys with sharps and a minor second
+    /// in keys with flats. If this is not possible, [`ChromaticPitch::to_pitch`] is used.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{scale::Key, ChromaticPitch, Pitch, ParsePitchError};
+    /// # use std::str::FromStr;
+    /// let a_major = Key::major(Pitch::class_from_str("A")?);
+    /// assert_eq!(a_major.spell(ChromaticPitch::new(1)), Pitch::from_str("C#4")?);
+    /// assert_eq!(a_major.spell(ChromaticPitch::new(3)), Pitch::from_str("D#4")?);
+    /// assert_eq!(a_major.spell(ChromaticPitch::new(5)), Pitch::from_str("F4")?);
+    /// let f_major = Key::major(Pitch::class_from_str("F")?);
+    /// assert_eq!(f_major.spell(ChromaticPitch::new(4)), Pitch::from_str("E4")?);
+    /// assert_eq!(f_major.spell(ChromaticPitch::new(3)), Pitch::from_str("Eb4")?);
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn spell(&self, pitch: ChromaticPitch) -> Pitch {
+        let spell_as = |p: Pitch| {
+            ((p.to_chromatic().to_num() - pitch.to_num()) % 12 == 0)
+                .then(|| pitch.to_pitch_named(p.pitch_name()))
+        };
+        let second = match self.key_signature().fifths().signum() {
+            1 => Some(Interval::new(1, 0)),
+            -1 => Some(Interval::MIN_SECOND),
+            _ => None,
+        };
+        let chromatic = [
+            Interval::MIN_THIRD,
+            Interval::MAJ_THIRD,
+            Interval::AUG_FOURTH,
+            Interval::MIN_SIXTH,
+            Interval::MAJ_SIXTH,
+            Interval::MIN_SEVENTH,
+            Interval::MAJ_SEVENTH,
+        ];
+        self.scale
+            .iter_from_root(self.tonic)
+            .take(self.scale.0.len())
+            .find_map(spell_as)
+            .or_else(|| {
+                second
+                    .into_iter()
+                    .chain(chromatic)
+                    .find_map(|interval| spell_as(self.tonic + interval))
+            })
+            .unwrap_or_else(|| pitch.to_pitch())
     }
 }
 
@@ -357,4 +632,41 @@ mod test {
         check_next!(iter, "C5");
         check_next!(iter, "D5");
     }
+
+    #[test]
+    fn spell() {
+        let key = |tonic, scale| Key::new(Pitch::class_from_str(tonic).unwrap(), scale);
+        let cases = [
+            (
+                key("C", Scale::major()),
+                [(3, "Eb4"), (6, "F#4"), (8, "Ab4"), (10, "Bb4")],
+            ),
+            (
+                key("E", Scale::major()),
+                [(0, "C4"), (3, "D#4"), (7, "G4"), (10, "A#4")],
+            ),
+            (
+                key("D", Scale::major()),
+                [(5, "F4"), (8, "G#4"), (10, "Bb4"), (0, "C4")],
+            ),
+            (
+                key("Eb", Scale::major()),
+                [(6, "Gb4"), (9, "A4"), (11, "Cb5"), (1, "Db4")],
+            ),
+            (
+                key("A", Scale::harmonic_minor()),
+                [(5, "F4"), (7, "G4"), (8, "G#4"), (9, "A4")],
+            ),
+            (
+                key("D", Scale::minor()),
+                [(1, "C#4"), (6, "F#4"), (8, "G#4"), (11, "B4")],
+            ),
+        ];
+        for (key, expected) in cases {
+            for (steps, pitch) in expected {
+                let spelled = key.spell(ChromaticPitch::new(steps));
+                assert_eq!(spelled.to_string(), pitch, "{steps} steps in {key:?}");
+            }
+        }
+    }
 }
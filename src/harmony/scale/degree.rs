@@ -0,0 +1,305 @@
+use std::{fmt, str::FromStr};
+
+use crate::harmony::{Accidental, Interval, Octave, ParseError, ParseIntervalError, Pitch};
+
+use super::{Key, Scale};
+
+/// the chromatic movable do syllables for each diatonic step above do, lowered, natural and
+/// raised
+const SYLLABLES: [[Option<&str>; 3]; 7] = [
+    [None, Some("do"), Some("di")],
+    [Some("ra"), Some("re"), Some("ri")],
+    [Some("me"), Some("mi"), None],
+    [None, Some("fa"), Some("fi")],
+    [Some("se"), Some("sol"), Some("si")],
+    [Some("le"), Some("la"), Some("li")],
+    [Some("te"), Some("ti"), None],
+];
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A scale degree with a chromatic alteration, relative to the scale of a key
+///
+/// # FromStr and Display implementation
+/// A scale degree is written as an optional `^`, the accidental and the degree, so the lowered
+/// third is written as `^b3`. The display always includes the `^`.
+/// ```
+/// # use music_types::harmony::{scale::ScaleDegree, Accidental, ParseError};
+/// # use std::str::FromStr;
+/// assert_eq!(ScaleDegree::from_str("^b3")?, ScaleDegree::new(3, Accidental::FLAT));
+/// assert_eq!(ScaleDegree::from_str("#4")?.to_string(), "^#4");
+/// # Ok::<(), ParseError>(())
+/// ```
+pub struct ScaleDegree {
+    degree: u8,
+    accidental: Accidental,
+}
+
+impl ScaleDegree {
+    /// creates a scale degree, where the tonic is degree 1
+    ///
+    /// # Panics
+    /// Panics if the degree is not in 1..=7
+    pub fn new(degree: u8, accidental: Accidental) -> Self {
+        assert!((1..=7).contains(&degree), "scale degree must be in 1..=7");
+        Self { degree, accidental }
+    }
+
+    /// returns the degree, where the tonic is degree 1
+    pub fn degree(&self) -> u8 {
+        self.degree
+    }
+
+    /// returns the alteration of the degree relative to the scale
+    pub fn accidental(&self) -> Accidental {
+        self.accidental
+    }
+}
+
+impl fmt::Display for ScaleDegree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "^{}{}", self.accidental, self.degree)
+    }
+}
+
+impl FromStr for ScaleDegree {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rest = s.strip_prefix('^').unwrap_or(s);
+        let accidental = rest.trim_end_matches(|c: char| c.is_ascii_digit());
+        let degree = &rest[accidental.len()..];
+        let degree = u8::from_str(degree)
+            .ok()
+            .filter(|d| (1..=7).contains(d))
+            .ok_or_else(|| ParseIntervalError::InvalidNumber(degree.to_string()))?;
+        Ok(Self::new(degree, Accidental::from_str(accidental)?))
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A system of movable do solfège
+///
+/// In both systems syllables are chromatic alterations of the major scale on do: raised
+/// syllables are di, ri, fi, si and li and lowered syllables are ra, me, se, le and te.
+pub enum MovableDo {
+    /// The tonic of every key is do, so minor keys use me, le and te
+    #[default]
+    DoBased,
+    /// Keys with a minor third take do from their relative major, so the tonic of a minor key
+    /// is la and its raised seventh is si
+    LaBased,
+}
+
+impl Key {
+    /// Returns the scale degree of `pitch` in this key.
+    ///
+    /// The degree is found by spelling, so in C major D# is `^#2` and Eb is `^b3`.
+    /// Returns `None` if the scale has no degree with the staff position of `pitch`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{scale::{Key, ScaleDegree}, Pitch, ParseError};
+    /// # use std::str::FromStr;
+    /// let key = Key::major(Pitch::class_from_str("D")?);
+    /// assert_eq!(key.scale_degree(Pitch::from_str("F#5")?), Some(ScaleDegree::from_str("3")?));
+    /// assert_eq!(key.scale_degree(Pitch::from_str("G#3")?), Some(ScaleDegree::from_str("#4")?));
+    /// let minor = Key::minor(Pitch::class_from_str("D")?);
+    /// assert_eq!(minor.scale_degree(Pitch::from_str("F#5")?), Some(ScaleDegree::from_str("#3")?));
+    /// # Ok::<(), ParseError>(())
+    /// ```
+    pub fn scale_degree(&self, pitch: Pitch) -> Option<ScaleDegree> {
+        let interval = (pitch - self.tonic) % Octave;
+        let step = self.step(interval.diatonic)?;
+        let degree = u8::try_from(interval.diatonic + 1).ok()?;
+        Some(ScaleDegree::new(
+            degree,
+            Accidental::new(interval.chromatic - step.chromatic),
+        ))
+    }
+
+    /// Returns the pitch class on `degree` in this key.
+    ///
+    /// Returns `None` if the scale has no such degree.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{scale::{Key, ScaleDegree}, Pitch, ParseError};
+    /// # use std::str::FromStr;
+    /// let key = Key::major(Pitch::class_from_str("Eb")?);
+    /// let degree = ScaleDegree::from_str("^b7")?;
+    /// assert_eq!(key.degree_pitch(degree), Some(Pitch::class_from_str("Db")?));
+    /// # Ok::<(), ParseError>(())
+    /// ```
+    pub fn degree_pitch(&self, degree: ScaleDegree) -> Option<Pitch> {
+        let step = self.step(i16::from(degree.degree) - 1)?;
+        let alteration = Interval::new(degree.accidental.chromatic_shift(), 0);
+        Some((self.tonic + step + alteration) % Octave)
+    }
+
+    /// Returns the movable do syllable of `pitch` in this key.
+    ///
+    /// Returns `None` if the pitch is altered further than the chromatic syllables allow.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{scale::{Key, MovableDo}, Pitch, ParsePitchError};
+    /// # use std::str::FromStr;
+    /// let minor = Key::minor(Pitch::class_from_str("A")?);
+    /// let g_sharp = Pitch::from_str("G#4")?;
+    /// assert_eq!(minor.syllable(g_sharp, MovableDo::DoBased), Some("ti"));
+    /// assert_eq!(minor.syllable(g_sharp, MovableDo::LaBased), Some("si"));
+    /// let major = Key::major(Pitch::class_from_str("F")?);
+    /// assert_eq!(major.syllable(Pitch::from_str("E4")?, MovableDo::DoBased), Some("ti"));
+    /// assert_eq!(major.syllable(Pitch::from_str("Gb4")?, MovableDo::DoBased), Some("ra"));
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn syllable(&self, pitch: Pitch, system: MovableDo) -> Option<&'static str> {
+        let interval = (pitch - self.do_pitch(system)) % Octave;
+        let major = Scale::major().0[usize::try_from(interval.diatonic).ok()?];
+        let alteration = usize::try_from(interval.chromatic - major.chromatic + 1).ok()?;
+        SYLLABLES[usize::try_from(interval.diatonic).ok()?]
+            .get(alteration)
+            .copied()
+            .flatten()
+    }
+
+    /// Returns the pitch class of a movable do syllable in this key.
+    ///
+    /// The case is ignored and `so` is accepted for `sol`. Returns `None` for unknown
+    /// syllables.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::harmony::{scale::{Key, MovableDo}, Pitch, ParsePitchError};
+    /// let key = Key::minor(Pitch::class_from_str("C")?);
+    /// assert_eq!(key.syllable_pitch("me", MovableDo::DoBased), Some(Pitch::class_from_str("Eb")?));
+    /// assert_eq!(key.syllable_pitch("Fi", MovableDo::LaBased), Some(Pitch::class_from_str("A")?));
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn syllable_pitch(&self, syllable: &str, system: MovableDo) -> Option<Pitch> {
+        let syllable = syllable.to_lowercase();
+        let syllable = if syllable == "so" { "sol" } else { &syllable };
+        let (step, alteration) = SYLLABLES.iter().enumerate().find_map(|(step, names)| {
+            let alteration = names.iter().position(|name| *name == Some(syllable))?;
+            Some((step, alteration))
+        })?;
+        let major = Scale::major().0[step];
+        let alteration = i16::try_from(alteration).ok()? - 1;
+        Some((self.do_pitch(system) + major + Interval::new(alteration, 0)) % Octave)
+    }
+
+    /// returns the scale step with the given diatonic steps above the tonic
+    fn step(&self, diatonic: i16) -> Option<Interval> {
+        self.scale
+            .0
+            .iter()
+            .map(|interval| *interval % Octave)
+            .find(|interval| interval.diatonic == diatonic)
+    }
+
+    /// returns the pitch class which is do in the given system
+    fn do_pitch(&self, system: MovableDo) -> Pitch {
+        match system {
+            MovableDo::LaBased if self.step(2) == Some(Interval::MIN_THIRD) => {
+                (self.tonic + Interval::MIN_THIRD) % Octave
+            }
+            _ => self.tonic,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn degrees_round_trip() {
+        for tonic in ["C", "F#", "Bb", "Ab"] {
+            let tonic = Pitch::class_from_str(tonic).unwrap();
+            for key in [
+                Key::major(tonic),
+                Key::minor(tonic),
+                Key::new(tonic, Scale::dorian()),
+            ] {
+                for diatonic in 0..14 {
+                    for shift in -2..=2 {
+                        let pitch = (tonic + Interval::new(0, diatonic)) % Octave;
+                        let pitch = pitch.with_accidental(Accidental::new(shift));
+                        let degree = key.scale_degree(pitch).unwrap();
+                        assert_eq!(key.degree_pitch(degree), Some(pitch % Octave));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn chromatic_syllables() {
+        let key = Key::major(Pitch::class_from_str("D").unwrap());
+        let ascending: Vec<_> = [
+            "D", "D#", "E", "E#", "F#", "G", "G#", "A", "A#", "B", "B#", "C#",
+        ]
+        .iter()
+        .map(|s| key.syllable(Pitch::class_from_str(s).unwrap(), MovableDo::DoBased))
+        .collect();
+        assert_eq!(
+            ascending,
+            [
+                Some("do"),
+                Some("di"),
+                Some("re"),
+                Some("ri"),
+                Some("mi"),
+                Some("fa"),
+                Some("fi"),
+                Some("sol"),
+                Some("si"),
+                Some("la"),
+                Some("li"),
+                Some("ti")
+            ]
+        );
+        assert_eq!(
+            key.syllable(Pitch::class_from_str("F##").unwrap(), MovableDo::DoBased),
+            None
+        );
+        for system in [MovableDo::DoBased, MovableDo::LaBased] {
+            for row in SYLLABLES {
+                for syllable in row.into_iter().flatten() {
+                    let pitch = key.syllable_pitch(syllable, system).unwrap();
+                    assert_eq!(key.syllable(pitch, system), Some(syllable));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn la_based() {
+        let key = Key::minor(Pitch::class_from_str("E").unwrap());
+        let syllables: Vec<_> = ["E", "F#", "G", "A", "B", "C", "C#", "D", "D#"]
+            .iter()
+            .map(|s| key.syllable(Pitch::class_from_str(s).unwrap(), MovableDo::LaBased))
+            .collect();
+        let expected = ["la", "ti", "do", "re", "mi", "fa", "fi", "sol", "si"];
+        assert_eq!(syllables, expected.map(Some));
+        // the la-based system only moves do for keys with a minor third
+        let major = Key::major(Pitch::class_from_str("E").unwrap());
+        assert_eq!(
+            major.syllable(Pitch::class_from_str("E").unwrap(), MovableDo::LaBased),
+            Some("do")
+        );
+    }
+
+    #[test]
+    fn parse_degree() {
+        assert!(ScaleDegree::from_str("^8").is_err());
+        assert!(ScaleDegree::from_str("^x3").is_err());
+        assert!(ScaleDegree::from_str("^").is_err());
+        assert_eq!(
+            ScaleDegree::from_str("bb7").unwrap(),
+            ScaleDegree::new(7, Accidental::DOUBLE_FLAT)
+        );
+    }
+}
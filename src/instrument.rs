@@ -0,0 +1,14 @@
+//! This module contains models of instruments, like the fretboard of a guitar or the
+//! transposition of a clarinet
+
+mod fretboard;
+mod range;
+mod shapes;
+mod tablature;
+mod transposition;
+
+pub use fretboard::{FretPosition, Fretboard};
+pub use range::{InstrumentRange, Part, PitchRange, RangeLevel, RangeViolation};
+pub use shapes::{ShapeConstraints, Voicing};
+pub use tablature::{ParseTablatureError, TabNote, Tablature, Technique};
+pub use transposition::TransposingInstrument;
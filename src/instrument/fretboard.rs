@@ -0,0 +1,219 @@
+use std::str::FromStr;
+
+use crate::harmony::{ChromaticPitch, Pitch, scale::Key};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A position on a fretboard
+///
+/// Strings are counted from 0 in the order of [`Fretboard::strings`]. Frets are counted from
+/// the capo, so fret 0 is the open string, which sounds at the capo if there is one.
+pub struct FretPosition {
+    string: usize,
+    fret: u8,
+}
+
+impl FretPosition {
+    /// creates a position from the index of the string and the fret
+    pub fn new(string: usize, fret: u8) -> Self {
+        Self { string, fret }
+    }
+
+    /// returns the index of the string
+    pub fn string(&self) -> usize {
+        self.string
+    }
+
+    /// returns the fret above the capo, 0 for the open string
+    pub fn fret(&self) -> u8 {
+        self.fret
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The fretboard of a fretted instrument
+///
+/// A fretboard consists of the open pitches of its strings, the number of frets and the fret of
+/// a capo. The strings are usually given from the lowest to the highest, but any order works.
+///
+/// # Examples
+/// ```
+/// # use music_types::{harmony::{scale::Key, Pitch, ParsePitchError}, instrument::{Fretboard, FretPosition}};
+/// # use std::str::FromStr;
+/// let guitar = Fretboard::guitar();
+/// let position = FretPosition::new(1, 1);
+/// let b_flat = Key::major(Pitch::class_from_str("F")?);
+/// let e = Key::major(Pitch::class_from_str("E")?);
+/// assert_eq!(guitar.pitch_at(position, &b_flat), Some(Pitch::from_str("Bb2")?));
+/// assert_eq!(guitar.pitch_at(position, &e), Some(Pitch::from_str("A#2")?));
+///
+/// let positions = guitar.positions(Pitch::from_str("E4")?);
+/// assert_eq!(positions[0], FretPosition::new(1, 19));
+/// assert_eq!(positions.len(), 5);
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub struct Fretboard {
+    strings: Vec<Pitch>,
+    frets: u8,
+    capo: u8,
+}
+
+impl Fretboard {
+    /// creates a fretboard from the open pitches of the strings and the number of frets
+    pub fn new(strings: Vec<Pitch>, frets: u8) -> Self {
+        Self {
+            strings,
+            frets,
+            capo: 0,
+        }
+    }
+
+    /// creates a fretboard from the names of the open strings, see [`Pitch`]
+    fn from_names(strings: &[&str], frets: u8) -> Self {
+        let strings = strings
+            .iter()
+            .map(|s| Pitch::from_str(s).expect("names of open strings are valid"))
+            .collect();
+        Self::new(strings, frets)
+    }
+
+    /// creates a six string guitar with 22 frets in standard tuning, E2 A2 D3 G3 B3 E4
+    pub fn guitar() -> Self {
+        Self::from_names(&["E2", "A2", "D3", "G3", "B3", "E4"], 22)
+    }
+
+    /// creates a four string bass with 20 frets in standard tuning, E1 A1 D2 G2
+    pub fn bass() -> Self {
+        Self::from_names(&["E1", "A1", "D2", "G2"], 20)
+    }
+
+    /// creates a ukulele with 15 frets in reentrant standard tuning, G4 C4 E4 A4
+    pub fn ukulele() -> Self {
+        Self::from_names(&["G4", "C4", "E4", "A4"], 15)
+    }
+
+    /// Places a capo on the given fret.
+    ///
+    /// The capo is limited to the number of frets.
+    pub fn with_capo(mut self, capo: u8) -> Self {
+        self.capo = capo.min(self.frets);
+        self
+    }
+
+    /// returns the open pitches of the strings without capo
+    pub fn strings(&self) -> &[Pitch] {
+        &self.strings
+    }
+
+    /// returns the number of frets
+    pub fn frets(&self) -> u8 {
+        self.frets
+    }
+
+    /// returns the fret of the capo, 0 if there is none
+    pub fn capo(&self) -> u8 {
+        self.capo
+    }
+
+    /// returns the number of frets above the capo
+    pub fn playable_frets(&self) -> u8 {
+        self.frets - self.capo
+    }
+
+    /// Returns the chromatic pitch at `position`.
+    ///
+    /// Returns `None` if the string does not exist or the fret is above the last fret.
+    pub fn chromatic_at(&self, position: FretPosition) -> Option<ChromaticPitch> {
+        let open = self.strings.get(position.string)?;
+        if position.fret > self.playable_frets() {
+            return None;
+        }
+        Some(ChromaticPitch::new(
+            open.to_chromatic().to_num() + i16::from(self.capo + position.fret),
+        ))
+    }
+
+    /// Returns the pitch at `position` spelled in `key`, see [`Key::spell`].
+    ///
+    /// Returns `None` if the string does not exist or the fret is above the last fret.
+    pub fn pitch_at(&self, position: FretPosition, key: &Key) -> Option<Pitch> {
+        self.chromatic_at(position).map(|pitch| key.spell(pitch))
+    }
+
+    /// Returns all positions where `pitch` or an enharmonic equivalent can be played, sorted by
+    /// string and fret.
+    pub fn positions(&self, pitch: Pitch) -> Vec<FretPosition> {
+        let target = pitch.to_chromatic().to_num();
+        (0..self.strings.len())
+            .filter_map(|string| {
+                let open = self.chromatic_at(FretPosition::new(string, 0))?.to_num();
+                let fret = u8::try_from(target - open).ok()?;
+                let position = FretPosition::new(string, fret);
+                self.chromatic_at(position).map(|_| position)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn capo() {
+        let guitar = Fretboard::guitar().with_capo(2);
+        let key = Key::major(Pitch::class_from_str("D").unwrap());
+        assert_eq!(guitar.playable_frets(), 20);
+        let open: Vec<_> = (0..6)
+            .map(|s| {
+                guitar
+                    .pitch_at(FretPosition::new(s, 0), &key)
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(open, ["F#2", "B2", "E3", "A3", "C#4", "F#4"]);
+        assert_eq!(guitar.chromatic_at(FretPosition::new(0, 21)), None);
+        assert_eq!(guitar.chromatic_at(FretPosition::new(6, 0)), None);
+        assert!(guitar.positions(Pitch::from_str("E2").unwrap()).is_empty());
+        assert_eq!(Fretboard::guitar().with_capo(30).capo(), 22);
+    }
+
+    #[test]
+    fn positions() {
+        let guitar = Fretboard::guitar();
+        let c = Pitch::from_str("C4").unwrap();
+        let positions = guitar.positions(c);
+        assert_eq!(
+            positions,
+            [
+                FretPosition::new(0, 20),
+                FretPosition::new(1, 15),
+                FretPosition::new(2, 10),
+                FretPosition::new(3, 5),
+                FretPosition::new(4, 1)
+            ]
+        );
+        let key = Key::major(Pitch::class_from_str("C").unwrap());
+        for position in positions {
+            assert_eq!(guitar.pitch_at(position, &key), Some(c));
+        }
+        // enharmonic spellings share their positions
+        assert_eq!(
+            guitar.positions(Pitch::from_str("B#3").unwrap()),
+            guitar.positions(c)
+        );
+        // the reentrant g string of the ukulele is above the c string
+        let ukulele = Fretboard::ukulele();
+        assert_eq!(
+            ukulele.positions(Pitch::from_str("A4").unwrap()),
+            [
+                FretPosition::new(0, 2),
+                FretPosition::new(1, 9),
+                FretPosition::new(2, 5),
+                FretPosition::new(3, 0)
+            ]
+        );
+    }
+}
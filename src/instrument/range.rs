@@ -0,0 +1,529 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use super::TransposingInstrument;
+use crate::{harmony::Pitch, rhythm::Timed};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A range of pitches between two bounds, both included
+///
+/// Pitches are compared with [`Pitch::cmp_chromatic`], so enharmonic pitches are ordered by
+/// their staff position. For example Cb4 is above B3, so it is inside a range starting at B3 but
+/// outside a range ending at B3.
+///
+/// # Examples
+/// ```
+/// # use music_types::{harmony::{Pitch, ParsePitchError}, instrument::PitchRange};
+/// # use std::str::FromStr;
+/// let range = PitchRange::new(Pitch::from_str("B3")?, Pitch::from_str("B5")?);
+/// assert!(range.contains(Pitch::from_str("Cb4")?));
+/// assert!(!range.contains(Pitch::from_str("A#3")?));
+/// assert!(!range.contains(Pitch::from_str("Cb6")?));
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub struct PitchRange {
+    low: Pitch,
+    high: Pitch,
+}
+
+impl PitchRange {
+    /// creates the range from `low` to `high`, swapping the bounds if `low` is above `high`
+    pub fn new(low: Pitch, high: Pitch) -> Self {
+        if low.cmp_chromatic(&high) == Ordering::Greater {
+            Self {
+                low: high,
+                high: low,
+            }
+        } else {
+            Self { low, high }
+        }
+    }
+
+    /// creates a range from the names of its bounds, see [`Pitch`]
+    fn from_names(low: &str, high: &str) -> Self {
+        let pitch = |s| Pitch::from_str(s).expect("names of range bounds are valid");
+        Self::new(pitch(low), pitch(high))
+    }
+
+    /// returns the lowest pitch of the range
+    pub fn low(&self) -> Pitch {
+        self.low
+    }
+
+    /// returns the highest pitch of the range
+    pub fn high(&self) -> Pitch {
+        self.high
+    }
+
+    /// Compares `pitch` to the range.
+    ///
+    /// Returns `Less` if it is below the range, `Greater` if it is above the range and `Equal`
+    /// if it is inside the range.
+    pub fn compare(&self, pitch: Pitch) -> Ordering {
+        if pitch.cmp_chromatic(&self.low) == Ordering::Less {
+            Ordering::Less
+        } else if pitch.cmp_chromatic(&self.high) == Ordering::Greater {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    /// returns true if `pitch` is inside the range
+    pub fn contains(&self, pitch: Pitch) -> bool {
+        self.compare(pitch) == Ordering::Equal
+    }
+}
+
+impl fmt::Display for PitchRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.low, self.high)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The levels of the range of an instrument, ordered from the narrowest to the widest
+pub enum RangeLevel {
+    /// The range which every player can play comfortably
+    Comfortable,
+    /// The range which can be expected from professional players
+    Professional,
+    /// The range which can be played at all
+    Absolute,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The range of an instrument or voice at each [`RangeLevel`]
+///
+/// The ranges are given in concert pitch, see [`TransposingInstrument`] to convert written
+/// pitches.
+///
+/// [`TransposingInstrument`]: super::TransposingInstrument
+pub struct InstrumentRange {
+    comfortable: PitchRange,
+    professional: PitchRange,
+    absolute: PitchRange,
+}
+
+impl InstrumentRange {
+    /// creates the range from the ranges of each level, where each range should contain the
+    /// previous ones
+    pub fn new(comfortable: PitchRange, professional: PitchRange, absolute: PitchRange) -> Self {
+        Self {
+            comfortable,
+            professional,
+            absolute,
+        }
+    }
+
+    /// returns the range at `level`
+    pub fn range(&self, level: RangeLevel) -> PitchRange {
+        match level {
+            RangeLevel::Comfortable => self.comfortable,
+            RangeLevel::Professional => self.professional,
+            RangeLevel::Absolute => self.absolute,
+        }
+    }
+
+    /// returns the narrowest level whose range contains `pitch`, `None` if it is out of range
+    pub fn level(&self, pitch: Pitch) -> Option<RangeLevel> {
+        [
+            RangeLevel::Comfortable,
+            RangeLevel::Professional,
+            RangeLevel::Absolute,
+        ]
+        .into_iter()
+        .find(|level| self.range(*level).contains(pitch))
+    }
+
+    /// Returns the notes which are outside the range at `level`, in the order of `notes`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::{harmony::{Pitch, ParsePitchError}, instrument::{Part, RangeLevel}, rhythm::Timed};
+    /// # use std::str::FromStr;
+    /// let notes = ["G3", "E6", "F#3"]
+    ///     .iter()
+    ///     .map(|s| Ok(Timed::new(Pitch::from_str(s)?, 0.0, 1.0)))
+    ///     .collect::<Result<Vec<_>, ParsePitchError>>()?;
+    /// let violations = Part::Violin.range().check(&notes, RangeLevel::Comfortable);
+    /// assert_eq!(violations.len(), 1);
+    /// assert_eq!(violations[0].index(), 2);
+    /// assert_eq!(violations[0].level(), None);
+    /// assert!(!violations[0].is_above());
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn check(&self, notes: &[Timed<Pitch>], level: RangeLevel) -> Vec<RangeViolation> {
+        let range = self.range(level);
+        notes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, note)| {
+                let pitch = note.value;
+                let ordering = range.compare(pitch);
+                (ordering != Ordering::Equal).then(|| RangeViolation {
+                    index,
+                    pitch,
+                    level: self.level(pitch),
+                    above: ordering == Ordering::Greater,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A note outside of a range, see [`InstrumentRange::check`]
+pub struct RangeViolation {
+    index: usize,
+    pitch: Pitch,
+    level: Option<RangeLevel>,
+    above: bool,
+}
+
+impl RangeViolation {
+    /// returns the index of the note in the checked notes
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// returns the pitch of the note
+    pub fn pitch(&self) -> Pitch {
+        self.pitch
+    }
+
+    /// returns the narrowest level whose range contains the note, `None` if it is unplayable
+    pub fn level(&self) -> Option<RangeLevel> {
+        self.level
+    }
+
+    /// returns true if the note is above the range, false if it is below
+    pub fn is_above(&self) -> bool {
+        self.above
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A standard orchestral, band or vocal part with a known range
+pub enum Part {
+    /// The violin
+    Violin,
+    /// The viola
+    Viola,
+    /// The cello
+    Cello,
+    /// The double bass, including the low C extension
+    DoubleBass,
+    /// The piccolo
+    Piccolo,
+    /// The flute, including the low B foot
+    Flute,
+    /// The oboe
+    Oboe,
+    /// The english horn
+    EnglishHorn,
+    /// The clarinet in Bb
+    Clarinet,
+    /// The bass clarinet, including the low C extension
+    BassClarinet,
+    /// The bassoon
+    Bassoon,
+    /// The contrabassoon
+    Contrabassoon,
+    /// The soprano saxophone
+    SopranoSaxophone,
+    /// The alto saxophone, including the altissimo register
+    AltoSaxophone,
+    /// The tenor saxophone, including the altissimo register
+    TenorSaxophone,
+    /// The baritone saxophone, including the low A
+    BaritoneSaxophone,
+    /// The horn in F
+    Horn,
+    /// The trumpet in Bb
+    Trumpet,
+    /// The tenor trombone
+    Trombone,
+    /// The bass trombone
+    BassTrombone,
+    /// The euphonium
+    Euphonium,
+    /// The tuba
+    Tuba,
+    /// A set of four timpani
+    Timpani,
+    /// The soprano voice
+    Soprano,
+    /// The mezzo-soprano voice
+    MezzoSoprano,
+    /// The alto voice
+    Alto,
+    /// The tenor voice
+    Tenor,
+    /// The baritone voice
+    Baritone,
+    /// The bass voice
+    Bass,
+}
+
+impl Part {
+    /// all parts of the database
+    pub const ALL: [Self; 29] = [
+        Self::Violin,
+        Self::Viola,
+        Self::Cello,
+        Self::DoubleBass,
+        Self::Piccolo,
+        Self::Flute,
+        Self::Oboe,
+        Self::EnglishHorn,
+        Self::Clarinet,
+        Self::BassClarinet,
+        Self::Bassoon,
+        Self::Contrabassoon,
+        Self::SopranoSaxophone,
+        Self::AltoSaxophone,
+        Self::TenorSaxophone,
+        Self::BaritoneSaxophone,
+        Self::Horn,
+        Self::Trumpet,
+        Self::Trombone,
+        Self::BassTrombone,
+        Self::Euphonium,
+        Self::Tuba,
+        Self::Timpani,
+        Self::Soprano,
+        Self::MezzoSoprano,
+        Self::Alto,
+        Self::Tenor,
+        Self::Baritone,
+        Self::Bass,
+    ];
+
+    /// returns the english name of the part
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Violin => "violin",
+            Self::Viola => "viola",
+            Self::Cello => "cello",
+            Self::DoubleBass => "double bass",
+            Self::Piccolo => "piccolo",
+            Self::Flute => "flute",
+            Self::Oboe => "oboe",
+            Self::EnglishHorn => "english horn",
+            Self::Clarinet => "clarinet",
+            Self::BassClarinet => "bass clarinet",
+            Self::Bassoon => "bassoon",
+            Self::Contrabassoon => "contrabassoon",
+            Self::SopranoSaxophone => "soprano saxophone",
+            Self::AltoSaxophone => "alto saxophone",
+            Self::TenorSaxophone => "tenor saxophone",
+            Self::BaritoneSaxophone => "baritone saxophone",
+            Self::Horn => "horn",
+            Self::Trumpet => "trumpet",
+            Self::Trombone => "trombone",
+            Self::BassTrombone => "bass trombone",
+            Self::Euphonium => "euphonium",
+            Self::Tuba => "tuba",
+            Self::Timpani => "timpani",
+            Self::Soprano => "soprano",
+            Self::MezzoSoprano => "mezzo-soprano",
+            Self::Alto => "alto",
+            Self::Tenor => "tenor",
+            Self::Baritone => "baritone",
+            Self::Bass => "bass",
+        }
+    }
+
+    /// returns the part with the given name ignoring ascii case, see [`Self::name`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|part| part.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// returns the range of the part in concert pitch
+    pub fn range(self) -> InstrumentRange {
+        // comfortable, professional and absolute range
+        let ranges = match self {
+            Self::Violin => [("G3", "E6"), ("G3", "C7"), ("G3", "A7")],
+            Self::Viola => [("C3", "E5"), ("C3", "E6"), ("C3", "A6")],
+            Self::Cello => [("C2", "A4"), ("C2", "A5"), ("C2", "C6")],
+            Self::DoubleBass => [("E1", "D3"), ("E1", "G4"), ("C1", "C5")],
+            Self::Piccolo => [("D5", "A7"), ("D5", "B7"), ("D5", "C8")],
+            Self::Flute => [("C4", "G6"), ("C4", "C7"), ("B3", "D7")],
+            Self::Oboe => [("C4", "D6"), ("Bb3", "F6"), ("Bb3", "A6")],
+            Self::EnglishHorn => [("E3", "G5"), ("E3", "Bb5"), ("E3", "C6")],
+            Self::Clarinet => [("D3", "Bb5"), ("D3", "F6"), ("D3", "Bb6")],
+            Self::BassClarinet => [("Db2", "F4"), ("Bb1", "Bb4"), ("Bb1", "F5")],
+            Self::Bassoon => [("Bb1", "G4"), ("Bb1", "D5"), ("Bb1", "E5")],
+            Self::Contrabassoon => [("Bb0", "F3"), ("Bb0", "Bb3"), ("Bb0", "C4")],
+            Self::SopranoSaxophone => [("Ab3", "Eb6"), ("Ab3", "E6"), ("Ab3", "C7")],
+            Self::AltoSaxophone => [("Db3", "Ab5"), ("Db3", "A5"), ("Db3", "F6")],
+            Self::TenorSaxophone => [("Ab2", "Eb5"), ("Ab2", "E5"), ("Ab2", "C6")],
+            Self::BaritoneSaxophone => [("Db2", "Ab4"), ("C2", "A4"), ("C2", "F5")],
+            Self::Horn => [("F2", "C5"), ("C2", "F5"), ("B1", "F5")],
+            Self::Trumpet => [("E3", "F5"), ("E3", "Bb5"), ("E3", "D6")],
+            Self::Trombone => [("E2", "F4"), ("E2", "C5"), ("Bb1", "F5")],
+            Self::BassTrombone => [("C2", "F4"), ("Bb1", "Bb4"), ("Bb0", "C5")],
+            Self::Euphonium => [("Bb1", "F4"), ("Bb1", "Bb4"), ("Bb0", "F5")],
+            Self::Tuba => [("F1", "Bb3"), ("D1", "F4"), ("Bb0", "F4")],
+            Self::Timpani => [("D2", "A3"), ("D2", "C4"), ("C2", "C4")],
+            Self::Soprano => [("C4", "G5"), ("C4", "C6"), ("Bb3", "F6")],
+            Self::MezzoSoprano => [("A3", "F5"), ("A3", "A5"), ("F3", "C6")],
+            Self::Alto => [("G3", "D5"), ("F3", "F5"), ("D3", "A5")],
+            Self::Tenor => [("C3", "G4"), ("C3", "C5"), ("A2", "D5")],
+            Self::Baritone => [("A2", "E4"), ("G2", "G4"), ("E2", "Bb4")],
+            Self::Bass => [("E2", "C4"), ("E2", "E4"), ("C2", "G4")],
+        };
+        let [comfortable, professional, absolute] =
+            ranges.map(|(low, high)| PitchRange::from_names(low, high));
+        InstrumentRange::new(comfortable, professional, absolute)
+    }
+
+    /// returns the transposing instrument which plays the part, `None` if it is written at
+    /// concert pitch
+    pub fn transposition(self) -> Option<TransposingInstrument> {
+        match self {
+            Self::DoubleBass => Some(TransposingInstrument::DoubleBass),
+            Self::Piccolo => Some(TransposingInstrument::Piccolo),
+            Self::EnglishHorn => Some(TransposingInstrument::EnglishHorn),
+            Self::Clarinet => Some(TransposingInstrument::ClarinetBb),
+            Self::BassClarinet => Some(TransposingInstrument::BassClarinet),
+            Self::Contrabassoon => Some(TransposingInstrument::Contrabassoon),
+            Self::SopranoSaxophone => Some(TransposingInstrument::SopranoSaxophone),
+            Self::AltoSaxophone => Some(TransposingInstrument::AltoSaxophone),
+            Self::TenorSaxophone => Some(TransposingInstrument::TenorSaxophone),
+            Self::BaritoneSaxophone => Some(TransposingInstrument::BaritoneSaxophone),
+            Self::Horn => Some(TransposingInstrument::HornF),
+            Self::Trumpet => Some(TransposingInstrument::TrumpetBb),
+            _ => None,
+        }
+    }
+
+    /// Returns the range of the part as written, see [`Self::transposition`].
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::{harmony::{Pitch, ParsePitchError}, instrument::{Part, RangeLevel}};
+    /// # use std::str::FromStr;
+    /// let clarinet = Part::Clarinet;
+    /// assert_eq!(clarinet.range().range(RangeLevel::Absolute).low(), Pitch::from_str("D3")?);
+    /// let written = clarinet.written_range().range(RangeLevel::Absolute);
+    /// assert_eq!(written.low(), Pitch::from_str("E3")?);
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn written_range(self) -> InstrumentRange {
+        let range = self.range();
+        let Some(instrument) = self.transposition() else {
+            return range;
+        };
+        let written = |level| {
+            let concert = range.range(level);
+            PitchRange::new(
+                instrument.written_pitch(concert.low()),
+                instrument.written_pitch(concert.high()),
+            )
+        };
+        InstrumentRange::new(
+            written(RangeLevel::Comfortable),
+            written(RangeLevel::Professional),
+            written(RangeLevel::Absolute),
+        )
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pitch(s: &str) -> Pitch {
+        Pitch::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn levels_are_nested() {
+        for part in Part::ALL {
+            let range = part.range();
+            let levels = [
+                RangeLevel::Comfortable,
+                RangeLevel::Professional,
+                RangeLevel::Absolute,
+            ];
+            for pair in levels.windows(2) {
+                let (inner, outer) = (range.range(pair[0]), range.range(pair[1]));
+                assert!(outer.contains(inner.low()), "{part} {pair:?}");
+                assert!(outer.contains(inner.high()), "{part} {pair:?}");
+            }
+            assert_eq!(Part::from_name(part.name()), Some(part));
+        }
+    }
+
+    #[test]
+    fn transpositions() {
+        for part in Part::ALL {
+            if let Some(instrument) = part.transposition() {
+                assert!(instrument.name().starts_with(part.name()), "{part}");
+            }
+        }
+        let horn = Part::Horn.written_range().range(RangeLevel::Comfortable);
+        assert_eq!((horn.low(), horn.high()), (pitch("C3"), pitch("G5")));
+        assert_eq!(Part::Violin.written_range(), Part::Violin.range());
+    }
+
+    #[test]
+    fn enharmonic_bounds() {
+        let range = Part::Oboe.range();
+        // the lowest note of the oboe is Bb3
+        assert_eq!(range.level(pitch("Bb3")), Some(RangeLevel::Professional));
+        assert_eq!(range.level(pitch("A#3")), None);
+        assert_eq!(range.level(pitch("Cb4")), Some(RangeLevel::Professional));
+        // B#3 sounds like C4 but is written below it
+        assert_eq!(range.level(pitch("B#3")), Some(RangeLevel::Professional));
+        assert_eq!(range.level(pitch("C4")), Some(RangeLevel::Comfortable));
+        let high = PitchRange::new(pitch("C4"), pitch("B4"));
+        assert_eq!(high.compare(pitch("Cb5")), Ordering::Greater);
+        assert_eq!(high.compare(pitch("A##4")), Ordering::Equal);
+        assert_eq!(PitchRange::new(pitch("B4"), pitch("C4")), high);
+    }
+
+    #[test]
+    fn check() {
+        let notes: Vec<_> = ["C3", "A4", "B4", "D5", "C6"]
+            .iter()
+            .map(|s| Timed::new(pitch(s), 0.0, 1.0))
+            .collect();
+        let range = Part::Tenor.range();
+        let flagged = |level| {
+            range
+                .check(&notes, level)
+                .iter()
+                .map(|v| (v.index(), v.level(), v.is_above()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            flagged(RangeLevel::Comfortable),
+            [
+                (1, Some(RangeLevel::Professional), true),
+                (2, Some(RangeLevel::Professional), true),
+                (3, Some(RangeLevel::Absolute), true),
+                (4, None, true)
+            ]
+        );
+        assert_eq!(flagged(RangeLevel::Absolute), [(4, None, true)]);
+        assert_eq!(
+            range.check(&notes, RangeLevel::Absolute)[0].pitch(),
+            pitch("C6")
+        );
+    }
+}
@@ -0,0 +1,393 @@
+use std::fmt;
+
+use crate::harmony::{ChromaticOctave, ChromaticPitch, chord::Chord};
+
+use super::{FretPosition, Fretboard};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Constraints on the voicings found by [`Fretboard::voicings`]
+///
+/// By default the fretted notes of a voicing span at most 4 frets and need at most 4 fingers,
+/// open strings are allowed, at least 3 strings sound and the root is the lowest note.
+pub struct ShapeConstraints {
+    max_span: u8,
+    max_fingers: usize,
+    open_strings: bool,
+    min_strings: usize,
+    root_in_bass: bool,
+}
+
+impl Default for ShapeConstraints {
+    fn default() -> Self {
+        Self {
+            max_span: 4,
+            max_fingers: 4,
+            open_strings: true,
+            min_strings: 3,
+            root_in_bass: true,
+        }
+    }
+}
+
+impl ShapeConstraints {
+    /// sets the largest number of frets covered by the fretted notes, which is at least 1
+    pub fn with_max_span(mut self, max_span: u8) -> Self {
+        self.max_span = max_span.max(1);
+        self
+    }
+
+    /// sets the largest number of fingers, where a barre counts as one finger
+    pub fn with_max_fingers(mut self, max_fingers: usize) -> Self {
+        self.max_fingers = max_fingers;
+        self
+    }
+
+    /// Sets whether open strings may sound.
+    ///
+    /// Open strings are only combined with fretted notes in the first position, that is within
+    /// the span from the first fret.
+    pub fn with_open_strings(mut self, open_strings: bool) -> Self {
+        self.open_strings = open_strings;
+        self
+    }
+
+    /// sets the smallest number of sounding strings
+    pub fn with_min_strings(mut self, min_strings: usize) -> Self {
+        self.min_strings = min_strings;
+        self
+    }
+
+    /// sets whether the lowest note must be the root of the chord
+    pub fn with_root_in_bass(mut self, root_in_bass: bool) -> Self {
+        self.root_in_bass = root_in_bass;
+        self
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A voicing of a chord on a fretboard
+///
+/// The voicing stores a fret for each string, or `None` for muted strings.
+///
+/// # Display implementation
+/// Muted strings are written as `x`. If all frets are below 10 they are written without
+/// separators, like `x32010`, otherwise they are separated by spaces, like `x 10 12 12 12 10`.
+pub struct Voicing {
+    frets: Vec<Option<u8>>,
+}
+
+impl Voicing {
+    /// creates a voicing from the fret of each string, `None` for muted strings
+    pub fn new(frets: Vec<Option<u8>>) -> Self {
+        Self { frets }
+    }
+
+    /// returns the fret of each string, `None` for muted strings
+    pub fn frets(&self) -> &[Option<u8>] {
+        &self.frets
+    }
+
+    /// returns the positions of the sounding strings
+    pub fn positions(&self) -> Vec<FretPosition> {
+        self.frets
+            .iter()
+            .enumerate()
+            .filter_map(|(string, fret)| Some(FretPosition::new(string, (*fret)?)))
+            .collect()
+    }
+
+    /// Returns the number of fingers needed to fret the voicing.
+    ///
+    /// Strings on the lowest fret share one finger as a barre, if no string between them is
+    /// open or muted.
+    pub fn fingers(&self) -> usize {
+        let fretted: Vec<_> = self
+            .frets
+            .iter()
+            .enumerate()
+            .filter_map(|(string, fret)| Some((string, fret.filter(|f| *f > 0)?)))
+            .collect();
+        let Some(lowest) = fretted.iter().map(|(_, fret)| *fret).min() else {
+            return 0;
+        };
+        let barre: Vec<_> = fretted.iter().filter(|(_, f)| *f == lowest).collect();
+        let (first, last) = (barre[0].0, barre[barre.len() - 1].0);
+        let covered = self.frets[first..=last]
+            .iter()
+            .all(|fret| fret.is_some_and(|f| f >= lowest));
+        if covered {
+            fretted.len() - barre.len() + 1
+        } else {
+            fretted.len()
+        }
+    }
+
+    /// returns the number of frets covered by the fretted notes, 0 if all strings are open
+    pub fn span(&self) -> u8 {
+        let fretted = self.frets.iter().flatten().filter(|f| **f > 0);
+        match (fretted.clone().min(), fretted.max()) {
+            (Some(min), Some(max)) => max - min + 1,
+            _ => 0,
+        }
+    }
+
+    /// Returns a score for how hard the voicing is to play, lower is easier.
+    ///
+    /// The score adds the fingers, the span and the muted strings, where a muted string between
+    /// two sounding strings counts four times.
+    pub fn difficulty(&self) -> usize {
+        if self.frets.is_empty() {
+            return 0;
+        }
+        let sounding = |fret: &Option<u8>| fret.is_some();
+        let first = self.frets.iter().position(sounding).unwrap_or(0);
+        let last = self.frets.iter().rposition(sounding).unwrap_or(0);
+        let muted = self.frets.iter().filter(|fret| fret.is_none()).count();
+        let interior = self.frets[first..=last.max(first)]
+            .iter()
+            .filter(|fret| fret.is_none())
+            .count();
+        self.fingers() + usize::from(self.span()) + muted + 3 * interior
+    }
+}
+
+impl fmt::Display for Voicing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frets: Vec<_> = self
+            .frets
+            .iter()
+            .map(|fret| fret.map_or_else(|| "x".to_string(), |fret| fret.to_string()))
+            .collect();
+        let separator = if frets.iter().all(|f| f.len() == 1) {
+            ""
+        } else {
+            " "
+        };
+        write!(f, "{}", frets.join(separator))
+    }
+}
+
+impl Fretboard {
+    /// Returns the playable voicings of `chord`, ranked from the easiest.
+    ///
+    /// Every voicing contains all pitch classes of the chord and no other pitch classes.
+    /// Voicings are ranked by their [difficulty](Voicing::difficulty), then by the position on
+    /// the fretboard and then by the number of muted strings.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::{harmony::{chord::Chord, Pitch, ParsePitchError}, instrument::{Fretboard, ShapeConstraints}};
+    /// let guitar = Fretboard::guitar();
+    /// let c_major = Chord::major(Pitch::class_from_str("C")?);
+    /// let voicings = guitar.voicings(&c_major, &ShapeConstraints::default());
+    /// assert_eq!(voicings[0].to_string(), "x32010");
+    ///
+    /// let no_open = ShapeConstraints::default().with_open_strings(false);
+    /// let voicings = guitar.voicings(&c_major, &no_open);
+    /// assert_eq!(voicings[0].to_string(), "8 10 10 9 8 8");
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn voicings(&self, chord: &Chord, constraints: &ShapeConstraints) -> Vec<Voicing> {
+        let class = |pitch: ChromaticPitch| pitch % ChromaticOctave;
+        let mut classes: Vec<_> = Vec::new();
+        for pitch in chord.pitches() {
+            if !classes.contains(&class(pitch.to_chromatic())) {
+                classes.push(class(pitch.to_chromatic()));
+            }
+        }
+        let root = class(chord.root().to_chromatic());
+
+        let mut voicings = Vec::new();
+        for start in 1..=self.playable_frets().max(1) {
+            let window = start..start.saturating_add(constraints.max_span);
+            // the frets of each string which sound a chord tone, with the chord tone as a bit
+            let options: Vec<Vec<(Option<u8>, u32)>> = (0..self.strings().len())
+                .map(|string| {
+                    let open = (constraints.open_strings && start == 1).then_some(0);
+                    let frets = open.into_iter().chain(window.clone()).filter_map(|fret| {
+                        let pitch = self.chromatic_at(FretPosition::new(string, fret))?;
+                        let tone = classes.iter().position(|c| *c == class(pitch))?;
+                        Some((Some(fret), 1 << tone))
+                    });
+                    std::iter::once((None, 0)).chain(frets).collect()
+                })
+                .collect();
+            let search = Search {
+                constraints,
+                tones: (1 << classes.len()) - 1,
+            };
+            search.collect(&options, &mut Vec::new(), 0, &mut voicings);
+        }
+
+        voicings.sort_by(|a, b| a.frets.cmp(&b.frets));
+        voicings.dedup();
+        voicings.retain(|voicing| {
+            let pitches: Vec<_> = voicing
+                .positions()
+                .into_iter()
+                .filter_map(|position| self.chromatic_at(position))
+                .collect();
+            let bass = pitches.iter().min().map(|pitch| class(*pitch));
+            pitches.len() >= constraints.min_strings
+                && classes
+                    .iter()
+                    .all(|c| pitches.iter().any(|pitch| class(*pitch) == *c))
+                && (!constraints.root_in_bass || bass == Some(root))
+                && voicing.fingers() <= constraints.max_fingers
+                && voicing.span() <= constraints.max_span
+        });
+        voicings.sort_by_cached_key(|voicing| {
+            let muted = voicing.frets.iter().filter(|f| f.is_none()).count();
+            let position = voicing
+                .frets
+                .iter()
+                .flatten()
+                .filter(|f| **f > 0)
+                .min()
+                .copied();
+            (voicing.difficulty(), position, muted)
+        });
+        voicings
+    }
+}
+
+/// The search for voicings among the frets of each string which sound a chord tone
+struct Search<'a> {
+    constraints: &'a ShapeConstraints,
+    /// the bits of all chord tones
+    tones: u32,
+}
+
+impl Search<'_> {
+    /// Collects the combinations of the options of the remaining strings.
+    ///
+    /// Partial voicings are dropped as soon as they need too many fingers, span too many frets
+    /// or can no longer sound enough strings or all chord tones.
+    fn collect(
+        &self,
+        options: &[Vec<(Option<u8>, u32)>],
+        frets: &mut Vec<Option<u8>>,
+        sounding_tones: u32,
+        voicings: &mut Vec<Voicing>,
+    ) {
+        let sounding = frets.iter().flatten().count();
+        let missing = (self.tones & !sounding_tones).count_ones() as usize;
+        if sounding + options.len() < self.constraints.min_strings || missing > options.len() {
+            return;
+        }
+        let fretted = frets.iter().flatten().filter(|f| **f > 0);
+        if let (Some(lowest), Some(highest)) = (fretted.clone().min(), fretted.clone().max()) {
+            // more strings may only lower the barre, so every string above it keeps its own
+            // finger
+            let fingers = fretted.filter(|f| *f > lowest).count() + 1;
+            if fingers > self.constraints.max_fingers
+                || highest - lowest >= self.constraints.max_span
+            {
+                return;
+            }
+        }
+        let Some((first, rest)) = options.split_first() else {
+            voicings.push(Voicing::new(frets.clone()));
+            return;
+        };
+        for (fret, tone) in first {
+            frets.push(*fret);
+            self.collect(rest, frets, sounding_tones | tone, voicings);
+            frets.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::harmony::Pitch;
+
+    fn shapes(fretboard: &Fretboard, chord: &Chord, constraints: ShapeConstraints) -> Vec<String> {
+        fretboard
+            .voicings(chord, &constraints)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn open_chords() {
+        let guitar = Fretboard::guitar();
+        let chord = |s: &str| Chord::major(Pitch::class_from_str(s).unwrap());
+        let first = |chord: Chord| shapes(&guitar, &chord, ShapeConstraints::default())[0].clone();
+        assert_eq!(first(chord("G")), "320003");
+        assert_eq!(first(chord("D")), "xx0232");
+        assert_eq!(first(chord("E")), "022100");
+        assert_eq!(
+            first(Chord::minor(Pitch::class_from_str("A").unwrap())),
+            "x02210"
+        );
+    }
+
+    #[test]
+    fn barre() {
+        let voicing = Voicing::new([1, 3, 3, 2, 1, 1].map(Some).to_vec());
+        assert_eq!(voicing.fingers(), 4);
+        assert_eq!(voicing.span(), 3);
+        let interrupted = Voicing::new(vec![Some(1), Some(0), Some(1)]);
+        assert_eq!(interrupted.fingers(), 2);
+
+        let guitar = Fretboard::guitar();
+        let f = Chord::major(Pitch::class_from_str("F").unwrap());
+        let constraints = ShapeConstraints::default().with_min_strings(6);
+        assert_eq!(shapes(&guitar, &f, constraints)[0], "133211");
+    }
+
+    #[test]
+    fn constraints() {
+        let guitar = Fretboard::guitar();
+        let chord = Chord::dominant_seventh(Pitch::class_from_str("B").unwrap());
+        for voicing in guitar.voicings(&chord, &ShapeConstraints::default().with_max_span(3)) {
+            assert!(voicing.span() <= 3);
+            assert!(voicing.fingers() <= 4);
+            let bass = voicing
+                .positions()
+                .iter()
+                .filter_map(|p| guitar.chromatic_at(*p))
+                .min()
+                .unwrap();
+            assert_eq!(bass % ChromaticOctave, ChromaticPitch::new(11));
+        }
+        let two_fingers = ShapeConstraints::default().with_max_fingers(2);
+        assert!(guitar.voicings(&chord, &two_fingers).is_empty());
+
+        // wide spans on an eight string guitar are searched without listing every combination
+        let eight_strings: Vec<_> = ["F#1", "B1", "E2", "A2", "D3", "G3", "B3", "E4"]
+            .iter()
+            .map(|s| Pitch::from_str(s).unwrap())
+            .collect();
+        let fretboard = Fretboard::new(eight_strings, 24);
+        let chord = Chord::diminished_seventh(Pitch::class_from_str("C").unwrap());
+        let wide = ShapeConstraints::default().with_max_span(12);
+        let voicings = fretboard.voicings(&chord, &wide);
+        assert_eq!(voicings[0].to_string(), "x123121x");
+        assert!(voicings.iter().all(|v| v.span() <= 12 && v.fingers() <= 4));
+    }
+
+    #[test]
+    fn alternate_tuning() {
+        let open_g: Vec<_> = ["D2", "G2", "D3", "G3", "B3", "D4"]
+            .iter()
+            .map(|s| Pitch::from_str(s).unwrap())
+            .collect();
+        let fretboard = Fretboard::new(open_g, 22);
+        let g = Chord::major(Pitch::class_from_str("G").unwrap());
+        assert_eq!(
+            shapes(&fretboard, &g, ShapeConstraints::default())[0],
+            "x00000"
+        );
+        let large = Voicing::new(vec![None, Some(12), Some(10)]);
+        assert_eq!(large.to_string(), "x 12 10");
+        assert_eq!(Voicing::new(vec![]).difficulty(), 0);
+    }
+}
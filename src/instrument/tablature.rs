@@ -0,0 +1,636 @@
+use std::{error::Error, fmt, iter};
+
+use crate::{
+    harmony::{NamingSystem, Pitch, scale::Key},
+    rhythm::Timed,
+};
+
+use super::{FretPosition, Fretboard};
+
+#[derive(Debug)]
+/// Error that may occur when parsing tablature.
+pub enum ParseTablatureError {
+    /// A system has a different number of lines than the fretboard has strings
+    StringCount {
+        /// the number of the first line of the system, starting at 1
+        line: usize,
+        /// the number of strings of the fretboard
+        expected: usize,
+        /// the number of lines of the system
+        found: usize,
+    },
+    /// A fret which is not on the fretboard
+    InvalidFret {
+        /// the line number, starting at 1
+        line: usize,
+        /// the text which could not be parsed
+        text: String,
+    },
+    /// A technique which is not written between two notes on the same string
+    InvalidTechnique {
+        /// the line number, starting at 1
+        line: usize,
+        /// the symbol of the technique
+        symbol: char,
+    },
+}
+
+impl fmt::Display for ParseTablatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StringCount {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected {expected} strings but found {found} in the system on line {line}"
+            ),
+            Self::InvalidFret { line, text } => {
+                write!(f, "fret `{text}` on line {line} is not on the fretboard")
+            }
+            Self::InvalidTechnique { line, symbol } => {
+                write!(f, "`{symbol}` on line {line} is not between two notes")
+            }
+        }
+    }
+}
+
+impl Error for ParseTablatureError {}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A technique connecting a note to the previous note on the same string
+pub enum Technique {
+    /// A hammer-on, written `h`
+    HammerOn,
+    /// A pull-off, written `p`
+    PullOff,
+    /// A bend, written `b`, where the note is the fret whose pitch is reached by bending
+    Bend,
+    /// A slide, written `/` or `\`
+    Slide,
+}
+
+impl Technique {
+    /// returns the symbol used in tablature
+    pub fn symbol(self) -> char {
+        match self {
+            Self::HammerOn => 'h',
+            Self::PullOff => 'p',
+            Self::Bend => 'b',
+            Self::Slide => '/',
+        }
+    }
+
+    /// returns the technique written with `symbol`, if there is one
+    pub fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol {
+            'h' => Some(Self::HammerOn),
+            'p' => Some(Self::PullOff),
+            'b' => Some(Self::Bend),
+            '/' | '\\' => Some(Self::Slide),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A note of a tablature, given by its position and the technique which leads to it
+pub struct TabNote {
+    position: FretPosition,
+    technique: Option<Technique>,
+}
+
+impl TabNote {
+    /// creates a note which is picked at `position`
+    pub fn new(position: FretPosition) -> Self {
+        Self {
+            position,
+            technique: None,
+        }
+    }
+
+    /// sets the technique which connects the note to the previous note on its string
+    pub fn with_technique(mut self, technique: Technique) -> Self {
+        self.technique = Some(technique);
+        self
+    }
+
+    /// returns the position on the fretboard
+    pub fn position(&self) -> FretPosition {
+        self.position
+    }
+
+    /// returns the technique which leads to the note, `None` if it is picked
+    pub fn technique(&self) -> Option<Technique> {
+        self.technique
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A tablature for a fretted instrument
+///
+/// Time in a tablature is measured in columns, where bar lines take no time. The conversion from
+/// and to beats uses a fixed number of beats per column.
+///
+/// # Text format
+/// Each string is written on a line with an optional label followed by `|`, with the highest
+/// string on top. Frets are written as numbers, the techniques of [`Technique`] as their
+/// symbols in front of the fret, and `|` marks a bar line. All other characters, like `-`, are
+/// empty columns. Systems are separated by lines without `|` and continue the previous system
+/// after a bar line.
+/// A note lasts until the next note on its string or until the end of the tablature.
+///
+/// # Examples
+/// ```
+/// # use music_types::{harmony::{scale::Key, Pitch}, instrument::{Fretboard, Tablature, Technique}};
+/// # use std::str::FromStr;
+/// let text = "
+/// e|-----0---|--------|
+/// B|---1-----|-1h3----|
+/// G|-0-------|-----0--|
+/// D|---------|--------|
+/// A|3--------|3-------|
+/// E|---------|--------|
+/// ";
+/// let tab = Tablature::parse(text, Fretboard::guitar())?;
+/// assert_eq!(tab.bars(), [9.0]);
+/// assert_eq!(tab.notes()[6].value().technique(), Some(Technique::HammerOn));
+///
+/// let key = Key::major(Pitch::class_from_str("C")?);
+/// let notes = tab.pitches(&key, 0.5);
+/// assert_eq!(*notes[0].value(), Pitch::from_str("C3")?);
+/// assert_eq!(notes[0].duration(), 4.5);
+/// assert_eq!(Tablature::parse(&tab.to_string(), Fretboard::guitar())?, tab);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct Tablature {
+    fretboard: Fretboard,
+    notes: Vec<Timed<TabNote>>,
+    bars: Vec<f64>,
+    length: f64,
+}
+
+impl Tablature {
+    /// Creates a tablature without bar lines from notes measured in columns.
+    ///
+    /// The tablature ends with the last note.
+    pub fn new(fretboard: Fretboard, mut notes: Vec<Timed<TabNote>>) -> Self {
+        notes.sort_by(|a, b| {
+            let string = |note: &Timed<TabNote>| note.value.position.string();
+            a.onset.total_cmp(&b.onset).then(string(a).cmp(&string(b)))
+        });
+        let length = notes.iter().map(Timed::end).fold(0.0, f64::max);
+        Self {
+            fretboard,
+            notes,
+            bars: Vec::new(),
+            length,
+        }
+    }
+
+    /// Sets the bar lines, measured in columns from the start
+    ///
+    /// Bar lines at the start or after the end are removed.
+    pub fn with_bars(mut self, mut bars: Vec<f64>) -> Self {
+        bars.retain(|bar| 0.0 < *bar && *bar < self.length);
+        bars.sort_by(f64::total_cmp);
+        bars.dedup();
+        self.bars = bars;
+        self
+    }
+
+    /// Parses a tablature for `fretboard`, see the text format above.
+    ///
+    /// # Errors
+    /// Fails if a system does not have a line for each string, if a fret is not on the
+    /// fretboard or if a technique is not written directly between two notes.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn parse(text: &str, fretboard: Fretboard) -> Result<Self, ParseTablatureError> {
+        let count = fretboard.strings().len();
+        let mut notes = Vec::new();
+        let mut bars = Vec::new();
+        let mut offset = 0;
+        let mut system = Vec::new();
+        let lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()));
+        // an empty line at the end closes the last system
+        for (line, content) in lines.chain(iter::once((0, ""))) {
+            if let Some((_label, tab)) = content.split_once('|') {
+                system.push((line, tab));
+                continue;
+            }
+            if system.is_empty() {
+                continue;
+            }
+            if system.len() != count {
+                return Err(ParseTablatureError::StringCount {
+                    line: system[0].0,
+                    expected: count,
+                    found: system.len(),
+                });
+            }
+            let mut width = 0;
+            for (i, (line, tab)) in system.iter().enumerate() {
+                let string = count - 1 - i;
+                let bars = (i == 0).then_some(&mut bars);
+                let columns = parse_line(&fretboard, *line, tab, string, offset, &mut notes, bars)?;
+                width = width.max(columns);
+            }
+            system.clear();
+            offset += width;
+            // the end of a system is a bar line
+            bars.push(offset);
+        }
+
+        // every note lasts until the next note on its string
+        notes.sort_by_key(|(column, note): &(usize, TabNote)| (note.position.string(), *column));
+        let length = offset as f64;
+        let notes: Vec<_> = notes
+            .iter()
+            .enumerate()
+            .map(|(i, (column, note))| {
+                let end = match notes.get(i + 1) {
+                    Some((next, other)) if other.position.string() == note.position.string() => {
+                        *next as f64
+                    }
+                    _ => length,
+                };
+                let onset = *column as f64;
+                Timed::new(*note, onset, end - onset)
+            })
+            .collect();
+        let bars = bars.into_iter().map(|bar| bar as f64).collect();
+        let mut tab = Self::new(fretboard, notes).with_bars(bars);
+        tab.length = length;
+        Ok(tab)
+    }
+
+    /// Creates a tablature from pitches, using `beats_per_column` to convert the time.
+    ///
+    /// Each note is placed on a string which is not used by another note in the same column,
+    /// preferring open strings and frets close to the previous fretted note.
+    /// Returns `None` if a note cannot be placed.
+    pub fn from_pitches(
+        notes: &[Timed<Pitch>],
+        fretboard: Fretboard,
+        beats_per_column: f64,
+    ) -> Option<Self> {
+        let mut sorted: Vec<_> = notes.iter().collect();
+        sorted.sort_by(|a, b| a.onset.total_cmp(&b.onset));
+        let mut hand = 0;
+        let mut tab_notes: Vec<Timed<TabNote>> = Vec::new();
+        for note in sorted {
+            let onset = (note.onset / beats_per_column).round();
+            let taken = |string| {
+                tab_notes
+                    .iter()
+                    .any(|n| n.value.position.string() == string && (n.onset - onset).abs() < 0.5)
+            };
+            let position = fretboard
+                .positions(note.value)
+                .into_iter()
+                .filter(|position| !taken(position.string()))
+                .min_by_key(|position| match position.fret() {
+                    0 => (0, 0),
+                    fret => (fret.abs_diff(hand), fret),
+                })?;
+            if position.fret() > 0 {
+                hand = position.fret();
+            }
+            let duration = note.duration / beats_per_column;
+            tab_notes.push(Timed::new(TabNote::new(position), onset, duration));
+        }
+        Some(Self::new(fretboard, tab_notes))
+    }
+
+    /// returns the fretboard of the tablature
+    pub fn fretboard(&self) -> &Fretboard {
+        &self.fretboard
+    }
+
+    /// returns the notes sorted by onset and string, measured in columns
+    pub fn notes(&self) -> &[Timed<TabNote>] {
+        &self.notes
+    }
+
+    /// returns the bar lines, measured in columns
+    pub fn bars(&self) -> &[f64] {
+        &self.bars
+    }
+
+    /// returns the number of columns
+    pub fn length(&self) -> f64 {
+        self.length
+    }
+
+    /// Returns the pitches of the notes spelled in `key` with [`Key::spell`], using
+    /// `beats_per_column` to convert the time.
+    pub fn pitches(&self, key: &Key, beats_per_column: f64) -> Vec<Timed<Pitch>> {
+        self.notes
+            .iter()
+            .filter_map(|note| {
+                let pitch = self.fretboard.pitch_at(note.value.position, key)?;
+                let onset = note.onset * beats_per_column;
+                Some(Timed::new(pitch, onset, note.duration * beats_per_column))
+            })
+            .collect()
+    }
+}
+
+/// Parses the line of `string` after the label and returns the number of columns.
+///
+/// The notes are collected with their columns, bar lines are only collected if `bars` is given.
+fn parse_line(
+    fretboard: &Fretboard,
+    line: usize,
+    tab: &str,
+    string: usize,
+    offset: usize,
+    notes: &mut Vec<(usize, TabNote)>,
+    mut bars: Option<&mut Vec<usize>>,
+) -> Result<usize, ParseTablatureError> {
+    let mut column = 0;
+    let mut previous = false;
+    let mut technique = None;
+    let mut chars = tab.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '|' {
+            if let Some(bars) = bars.as_mut() {
+                bars.push(offset + column);
+            }
+            previous = false;
+            continue;
+        }
+        if c.is_ascii_digit() {
+            let mut text = c.to_string();
+            while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                text.push(digit);
+            }
+            let invalid = || ParseTablatureError::InvalidFret {
+                line,
+                text: text.clone(),
+            };
+            let position = FretPosition::new(string, text.parse().map_err(|_| invalid())?);
+            fretboard.chromatic_at(position).ok_or_else(invalid)?;
+            let note = TabNote {
+                position,
+                technique: technique.take(),
+            };
+            notes.push((offset + column, note));
+            previous = true;
+            column += text.len();
+            continue;
+        }
+        if let Some(symbol) = Technique::from_symbol(c) {
+            // a technique connects a note to a note directly after it
+            if !previous || !chars.peek().is_some_and(char::is_ascii_digit) {
+                return Err(ParseTablatureError::InvalidTechnique { line, symbol: c });
+            }
+            technique = Some(symbol);
+        }
+        previous = false;
+        column += 1;
+    }
+    Ok(column)
+}
+
+impl fmt::Display for Tablature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let column = |time: f64| time.round().max(0.0) as usize;
+        let count = self.fretboard.strings().len();
+        let write_frets = |shift: &dyn Fn(usize) -> usize| {
+            let mut grid = vec![vec!['-'; shift(column(self.length))]; count];
+            for note in &self.notes {
+                let row = &mut grid[note.value.position.string()];
+                let start = shift(column(note.onset));
+                let text: Vec<_> = note.value.position.fret().to_string().chars().collect();
+                if row.len() < start + text.len() {
+                    row.resize(start + text.len(), '-');
+                }
+                row[start..start + text.len()].copy_from_slice(&text);
+            }
+            grid
+        };
+        // a column is inserted in front of a note whose technique would overwrite the end of
+        // the previous note
+        let unshifted = write_frets(&|column| column);
+        let mut inserted: Vec<_> = self
+            .notes
+            .iter()
+            .filter(|note| note.value.technique.is_some())
+            .map(|note| (note.value.position.string(), column(note.onset)))
+            .filter(|(string, start)| {
+                start
+                    .checked_sub(1)
+                    .is_some_and(|before| unshifted[*string][before] != '-')
+            })
+            .map(|(_, start)| start)
+            .collect();
+        inserted.sort_unstable();
+        inserted.dedup();
+        let shift = |column: usize| column + inserted.iter().filter(|i| **i <= column).count();
+        let mut grid = write_frets(&shift);
+        for note in &self.notes {
+            let row = &mut grid[note.value.position.string()];
+            let start = shift(column(note.onset));
+            if let (Some(technique), Some(before)) = (note.value.technique, start.checked_sub(1))
+                && row[before] == '-'
+            {
+                row[before] = technique.symbol();
+            }
+        }
+        let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+        let bars: Vec<_> = self
+            .bars
+            .iter()
+            .map(|bar| column(*bar))
+            .map(|bar| bar + inserted.iter().filter(|i| **i < bar).count())
+            .collect();
+        let labels: Vec<_> = self
+            .fretboard
+            .strings()
+            .iter()
+            .map(|open| NamingSystem::English.class_name(open.pitch_name(), open.accidental()))
+            .collect();
+        let label_width = labels.iter().map(String::len).max().unwrap_or(0);
+        for string in (0..count).rev() {
+            write!(f, "{:<label_width$}|", labels[string])?;
+            for i in 0..width {
+                if bars.contains(&i) {
+                    write!(f, "|")?;
+                }
+                write!(f, "{}", grid[string].get(i).unwrap_or(&'-'))?;
+            }
+            writeln!(f, "|")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let text = "Intro
+G|--0--|
+D|--2--|
+A|0-4p2|
+
+G|-------12-|
+D|-5b7------|
+A|----------|
+";
+        let strings = ["A1", "D2", "G2"].map(|s| Pitch::from_str(s).unwrap());
+        let bass = Fretboard::new(strings.to_vec(), 20);
+        let tab = Tablature::parse(text, bass).unwrap();
+        assert_eq!((tab.length(), tab.bars()), (15.0, [5.0].as_slice()));
+        let notes: Vec<_> = tab
+            .notes()
+            .iter()
+            .map(|note| {
+                let value = note.value();
+                let position = value.position();
+                let timing = (note.onset(), note.duration());
+                (
+                    timing,
+                    position.string(),
+                    position.fret(),
+                    value.technique(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            notes,
+            [
+                ((0.0, 2.0), 0, 0, None),
+                ((2.0, 2.0), 0, 4, None),
+                ((2.0, 4.0), 1, 2, None),
+                ((2.0, 10.0), 2, 0, None),
+                ((4.0, 11.0), 0, 2, Some(Technique::PullOff)),
+                ((6.0, 2.0), 1, 5, None),
+                ((8.0, 7.0), 1, 7, Some(Technique::Bend)),
+                ((12.0, 3.0), 2, 12, None),
+            ]
+        );
+        assert_eq!(
+            tab.to_string(),
+            "G|--0--|-------12-|\nD|--2--|-5b7------|\nA|0-4p2|----------|\n"
+        );
+    }
+
+    #[test]
+    fn adjacent_techniques() {
+        let bass = Fretboard::new(
+            ["E1", "A1", "D2", "G2"]
+                .map(|s| Pitch::from_str(s).unwrap())
+                .to_vec(),
+            24,
+        );
+        let note = |fret| TabNote::new(FretPosition::new(3, fret));
+        let notes = vec![
+            Timed::new(note(12), 0.0, 2.0),
+            Timed::new(note(14).with_technique(Technique::HammerOn), 2.0, 2.0),
+            Timed::new(TabNote::new(FretPosition::new(0, 3)), 2.0, 2.0),
+        ];
+        let tab = Tablature::new(bass.clone(), notes);
+        let text = tab.to_string();
+        assert_eq!(text, "G|12h14|\nD|-----|\nA|-----|\nE|---3-|\n");
+        let parsed = Tablature::parse(&text, bass).unwrap();
+        let frets: Vec<_> = parsed
+            .notes()
+            .iter()
+            .map(|n| n.value().position().fret())
+            .collect();
+        assert_eq!(frets, [12, 3, 14]);
+    }
+
+    #[test]
+    fn errors() {
+        let guitar = Fretboard::guitar;
+        let missing = "e|--|\nB|--|\n";
+        assert!(matches!(
+            Tablature::parse(missing, guitar()),
+            Err(ParseTablatureError::StringCount {
+                line: 1,
+                expected: 6,
+                found: 2
+            })
+        ));
+        let high = "e|-|\nB|-|\nG|-|\nD|-|\nA|-|\nE|-23-|\n";
+        assert!(matches!(
+            Tablature::parse(high, guitar()),
+            Err(ParseTablatureError::InvalidFret { line: 6, .. })
+        ));
+        let technique = |e| format!("e|{e}|\nB|-|\nG|-|\nD|-|\nA|-|\nE|-|\n");
+        for (e, symbol) in [
+            ("-h2-", 'h'),
+            // techniques after empty columns or bar lines
+            ("0---h--2", 'h'),
+            ("0|p2", 'p'),
+            // techniques without a following note
+            ("0b--2", 'b'),
+            ("5/|7", '/'),
+            ("0h", 'h'),
+            ("0hp2", 'h'),
+        ] {
+            assert!(
+                matches!(
+                    Tablature::parse(&technique(e), guitar()),
+                    Err(ParseTablatureError::InvalidTechnique { line: 1, symbol: s }) if s == symbol
+                ),
+                "{e} was parsed"
+            );
+        }
+        assert!(Tablature::parse(&technique("0h2p0"), guitar()).is_ok());
+    }
+
+    #[test]
+    fn from_pitches() {
+        let notes: Vec<_> = ["E2", "B2", "E3", "G#3", "B3", "E4", "C5"]
+            .iter()
+            .zip(0_u8..)
+            .map(|(s, i)| Timed::new(Pitch::from_str(s).unwrap(), f64::from(i) * 0.5, 0.5))
+            .collect();
+        let chord = Timed::new(Pitch::from_str("E2").unwrap(), 3.5, 1.0);
+        let notes = [
+            notes,
+            vec![chord, chord.map(|_| Pitch::from_str("Bb2").unwrap())],
+        ]
+        .concat();
+        let tab = Tablature::from_pitches(&notes, Fretboard::guitar(), 0.25)
+            .unwrap()
+            .with_bars(vec![14.0]);
+        assert_eq!(
+            tab.to_string(),
+            "E|----------0-8-|----|
+B|--------0-----|----|
+G|------1-------|----|
+D|----2---------|----|
+A|--2-----------|1---|
+E|0-------------|0---|
+"
+        );
+        let key = Key::major(Pitch::class_from_str("E").unwrap());
+        let pitches = tab.pitches(&key, 0.25);
+        assert_eq!(pitches[6].value().to_string(), "C5");
+        let a_sharp = Pitch::from_str("A#2").unwrap();
+        assert_eq!(pitches[8], Timed::new(a_sharp, 3.5, 1.0));
+
+        let low = [Timed::new(Pitch::from_str("D2").unwrap(), 0.0, 1.0)];
+        assert_eq!(
+            Tablature::from_pitches(&low, Fretboard::guitar(), 1.0),
+            None
+        );
+    }
+}
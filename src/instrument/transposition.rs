@@ -0,0 +1,333 @@
+use std::fmt;
+
+use crate::harmony::{Interval, Pitch, scale::KeySignature};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An instrument whose part is written at a different pitch than it sounds
+///
+/// The transposition of an instrument is the interval from the written to the sounding concert
+/// pitch, for example a major second down for the clarinet in Bb.
+///
+/// # Examples
+/// ```
+/// # use music_types::{harmony::{scale::KeySignature, Pitch, ParsePitchError}, instrument::TransposingInstrument};
+/// # use std::str::FromStr;
+/// let horn = TransposingInstrument::HornF;
+/// assert_eq!(horn.written_pitch(Pitch::from_str("Bb3")?), Pitch::from_str("F4")?);
+/// assert_eq!(horn.concert_pitch(Pitch::from_str("C5")?), Pitch::from_str("F4")?);
+///
+/// let alto = TransposingInstrument::from_name("Alto Saxophone").unwrap();
+/// let concert = KeySignature::major(Pitch::class_from_str("Eb")?);
+/// assert_eq!(alto.written_key_signature(&concert).fifths(), 0);
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub enum TransposingInstrument {
+    /// The piccolo, sounding an octave higher
+    Piccolo,
+    /// The alto flute in G, sounding a perfect fourth lower
+    AltoFlute,
+    /// The bass flute in C, sounding an octave lower
+    BassFlute,
+    /// The english horn in F, sounding a perfect fifth lower
+    EnglishHorn,
+    /// The clarinet in Eb, sounding a minor third higher
+    ClarinetEb,
+    /// The clarinet in Bb, sounding a major second lower
+    ClarinetBb,
+    /// The clarinet in A, sounding a minor third lower
+    ClarinetA,
+    /// The bass clarinet in Bb written in treble clef, sounding a major ninth lower
+    BassClarinet,
+    /// The contrabassoon, sounding an octave lower
+    Contrabassoon,
+    /// The soprano saxophone in Bb, sounding a major second lower
+    SopranoSaxophone,
+    /// The alto saxophone in Eb, sounding a major sixth lower
+    AltoSaxophone,
+    /// The tenor saxophone in Bb, sounding a major ninth lower
+    TenorSaxophone,
+    /// The baritone saxophone in Eb, sounding an octave and a major sixth lower
+    BaritoneSaxophone,
+    /// The horn in F, sounding a perfect fifth lower
+    HornF,
+    /// The trumpet in Bb, sounding a major second lower
+    TrumpetBb,
+    /// The trumpet in D, sounding a major second higher
+    TrumpetD,
+    /// The flugelhorn in Bb, sounding a major second lower
+    Flugelhorn,
+    /// The guitar, sounding an octave lower
+    Guitar,
+    /// The double bass, sounding an octave lower
+    DoubleBass,
+    /// The celesta, sounding an octave higher
+    Celesta,
+    /// The glockenspiel, sounding two octaves higher
+    Glockenspiel,
+    /// The xylophone, sounding an octave higher
+    Xylophone,
+}
+
+impl TransposingInstrument {
+    /// all instruments of the registry
+    pub const ALL: [Self; 22] = [
+        Self::Piccolo,
+        Self::AltoFlute,
+        Self::BassFlute,
+        Self::EnglishHorn,
+        Self::ClarinetEb,
+        Self::ClarinetBb,
+        Self::ClarinetA,
+        Self::BassClarinet,
+        Self::Contrabassoon,
+        Self::SopranoSaxophone,
+        Self::AltoSaxophone,
+        Self::TenorSaxophone,
+        Self::BaritoneSaxophone,
+        Self::HornF,
+        Self::TrumpetBb,
+        Self::TrumpetD,
+        Self::Flugelhorn,
+        Self::Guitar,
+        Self::DoubleBass,
+        Self::Celesta,
+        Self::Glockenspiel,
+        Self::Xylophone,
+    ];
+
+    /// returns the english name of the instrument, like `clarinet in Bb`
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Piccolo => "piccolo",
+            Self::AltoFlute => "alto flute",
+            Self::BassFlute => "bass flute",
+            Self::EnglishHorn => "english horn",
+            Self::ClarinetEb => "clarinet in Eb",
+            Self::ClarinetBb => "clarinet in Bb",
+            Self::ClarinetA => "clarinet in A",
+            Self::BassClarinet => "bass clarinet",
+            Self::Contrabassoon => "contrabassoon",
+            Self::SopranoSaxophone => "soprano saxophone",
+            Self::AltoSaxophone => "alto saxophone",
+            Self::TenorSaxophone => "tenor saxophone",
+            Self::BaritoneSaxophone => "baritone saxophone",
+            Self::HornF => "horn in F",
+            Self::TrumpetBb => "trumpet in Bb",
+            Self::TrumpetD => "trumpet in D",
+            Self::Flugelhorn => "flugelhorn",
+            Self::Guitar => "guitar",
+            Self::DoubleBass => "double bass",
+            Self::Celesta => "celesta",
+            Self::Glockenspiel => "glockenspiel",
+            Self::Xylophone => "xylophone",
+        }
+    }
+
+    /// returns the instrument with the given name ignoring ascii case, see [`Self::name`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|instrument| instrument.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// returns the interval from the written pitch to the concert pitch
+    pub fn transposition(self) -> Interval {
+        let ninth = Interval::OCTAVE + Interval::MAJ_SECOND;
+        match self {
+            Self::Piccolo | Self::Celesta | Self::Xylophone => Interval::OCTAVE,
+            Self::AltoFlute => -Interval::FOURTH,
+            Self::BassFlute | Self::Contrabassoon | Self::Guitar | Self::DoubleBass => {
+                -Interval::OCTAVE
+            }
+            Self::EnglishHorn | Self::HornF => -Interval::FIFTH,
+            Self::ClarinetEb => Interval::MIN_THIRD,
+            Self::ClarinetBb | Self::SopranoSaxophone | Self::TrumpetBb | Self::Flugelhorn => {
+                -Interval::MAJ_SECOND
+            }
+            Self::ClarinetA => -Interval::MIN_THIRD,
+            Self::BassClarinet | Self::TenorSaxophone => -ninth,
+            Self::AltoSaxophone => -Interval::MAJ_SIXTH,
+            Self::BaritoneSaxophone => -(Interval::OCTAVE + Interval::MAJ_SIXTH),
+            Self::TrumpetD => Interval::MAJ_SECOND,
+            Self::Glockenspiel => Interval::OCTAVE + Interval::OCTAVE,
+        }
+    }
+
+    /// returns the sounding pitch of the `written` pitch
+    pub fn concert_pitch(self, written: Pitch) -> Pitch {
+        written + self.transposition()
+    }
+
+    /// returns the pitch which is written for the `concert` pitch
+    pub fn written_pitch(self, concert: Pitch) -> Pitch {
+        concert - self.transposition()
+    }
+
+    /// Returns the key signature of the part for a piece in the `concert` key signature.
+    ///
+    /// Key signatures with more than 6 sharps or flats are respelled, see
+    /// [`KeySignature::simplified`]. Use [`Self::written_pitch_in_key`] to spell the notes of
+    /// the part to match.
+    pub fn written_key_signature(self, concert: &KeySignature) -> KeySignature {
+        concert.transpose(self.written_transposition(concert))
+    }
+
+    /// Returns the concert key signature of a part written in the `written` key signature.
+    ///
+    /// Key signatures with more than 6 sharps or flats are respelled, see
+    /// [`KeySignature::simplified`].
+    pub fn concert_key_signature(self, written: &KeySignature) -> KeySignature {
+        written.transpose(self.concert_transposition(written))
+    }
+
+    /// Returns the pitch which is written for the `concert` pitch in a piece in the `concert_key`
+    /// key signature.
+    ///
+    /// The pitch is respelled like the [written key signature][Self::written_key_signature].
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::{harmony::{scale::KeySignature, Pitch, ParsePitchError}, instrument::TransposingInstrument};
+    /// # use std::str::FromStr;
+    /// let clarinet = TransposingInstrument::ClarinetBb;
+    /// let b_major = KeySignature::major(Pitch::class_from_str("B")?);
+    /// assert_eq!(clarinet.written_key_signature(&b_major).fifths(), -5);
+    /// let b_4 = Pitch::from_str("B4")?;
+    /// assert_eq!(clarinet.written_pitch(b_4), Pitch::from_str("C#5")?);
+    /// assert_eq!(clarinet.written_pitch_in_key(b_4, &b_major), Pitch::from_str("Db5")?);
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn written_pitch_in_key(self, concert: Pitch, concert_key: &KeySignature) -> Pitch {
+        concert + self.written_transposition(concert_key)
+    }
+
+    /// Returns the sounding pitch of the `written` pitch in a part in the `written_key` key
+    /// signature.
+    ///
+    /// The pitch is respelled like the [concert key signature][Self::concert_key_signature].
+    pub fn concert_pitch_in_key(self, written: Pitch, written_key: &KeySignature) -> Pitch {
+        written + self.concert_transposition(written_key)
+    }
+
+    /// returns the interval from concert to written pitches for the `concert` key signature,
+    /// respelled so that the written key signature has at most 6 sharps or flats
+    fn written_transposition(self, concert: &KeySignature) -> Interval {
+        respelled(-self.transposition(), concert)
+    }
+
+    /// returns the interval from written to concert pitches for the `written` key signature,
+    /// respelled so that the concert key signature has at most 6 sharps or flats
+    fn concert_transposition(self, written: &KeySignature) -> Interval {
+        respelled(self.transposition(), written)
+    }
+}
+
+/// returns `interval` respelled by diminished seconds, so that transposing `key` by it results in
+/// at most 6 sharps or flats, like [`KeySignature::simplified`]
+fn respelled(interval: Interval, key: &KeySignature) -> Interval {
+    // a diminished second is twelve fifths down
+    let diminished_second = Interval::new(0, 1);
+    match key.transpose(interval).fifths() {
+        fifths if fifths > 6 => respelled(interval + diminished_second, key),
+        fifths if fifths < -6 => respelled(interval + -diminished_second, key),
+        _ => interval,
+    }
+}
+
+impl fmt::Display for TransposingInstrument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::harmony::scale::{AccidentalCalulator, Scale};
+
+    #[test]
+    fn pitches() {
+        let pitch = |s| Pitch::from_str(s).unwrap();
+        let cases = [
+            (TransposingInstrument::ClarinetBb, "D5", "C5"),
+            (TransposingInstrument::ClarinetA, "Eb5", "C5"),
+            (TransposingInstrument::AltoSaxophone, "A5", "C5"),
+            (TransposingInstrument::TenorSaxophone, "D5", "C4"),
+            (TransposingInstrument::BaritoneSaxophone, "A5", "C4"),
+            (TransposingInstrument::Piccolo, "C5", "C6"),
+            (TransposingInstrument::ClarinetEb, "A4", "C5"),
+            (TransposingInstrument::Glockenspiel, "G5", "G7"),
+        ];
+        for (instrument, written, concert) in cases {
+            assert_eq!(instrument.concert_pitch(pitch(written)), pitch(concert));
+            assert_eq!(instrument.written_pitch(pitch(concert)), pitch(written));
+        }
+        // spelling is kept exactly
+        let clarinet = TransposingInstrument::ClarinetBb;
+        assert_eq!(clarinet.written_pitch(pitch("F#4")), pitch("G#4"));
+        assert_eq!(clarinet.written_pitch(pitch("Gb4")), pitch("Ab4"));
+    }
+
+    #[test]
+    fn key_signatures() {
+        let major = |s| KeySignature::major(Pitch::class_from_str(s).unwrap());
+        let clarinet = TransposingInstrument::ClarinetBb;
+        assert_eq!(clarinet.written_key_signature(&major("C")).fifths(), 2);
+        assert_eq!(clarinet.written_key_signature(&major("Ab")).fifths(), -2);
+        // C# major, respelled as Db major
+        assert_eq!(clarinet.written_key_signature(&major("B")).fifths(), -5);
+        assert_eq!(clarinet.concert_key_signature(&major("D")).fifths(), 0);
+        let horn = TransposingInstrument::HornF;
+        assert_eq!(horn.written_key_signature(&major("Eb")).fifths(), -2);
+        let clarinet_a = TransposingInstrument::ClarinetA;
+        assert_eq!(clarinet_a.written_key_signature(&major("F#")).fifths(), 3);
+        // Fb major, respelled as E major
+        assert_eq!(clarinet_a.written_key_signature(&major("Db")).fifths(), 4);
+        let guitar = TransposingInstrument::Guitar;
+        assert_eq!(guitar.written_key_signature(&major("E")).fifths(), 4);
+    }
+
+    #[test]
+    fn pitches_in_key() {
+        // the scale of every concert key is written without accidentals in the written key
+        for instrument in TransposingInstrument::ALL {
+            for tonic in ["C", "F#", "Gb", "B", "Db", "C#", "Cb", "A", "Eb"] {
+                let tonic = Pitch::class_from_str(tonic).unwrap();
+                let concert = KeySignature::major(tonic);
+                let written = instrument.written_key_signature(&concert);
+                let mut calculator = AccidentalCalulator::from_key_signature(written.clone());
+                for pitch in Scale::major().iter_from_root(tonic).take(7) {
+                    let written_pitch = instrument.written_pitch_in_key(pitch, &concert);
+                    assert_eq!(
+                        calculator.get_and_update(written_pitch),
+                        None,
+                        "{instrument}"
+                    );
+                    // F# and Gb major may come back respelled as each other
+                    if concert.fifths().abs() <= 5 {
+                        let back = instrument.concert_pitch_in_key(written_pitch, &written);
+                        assert_eq!(back, pitch, "{instrument}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn names() {
+        for instrument in TransposingInstrument::ALL {
+            assert_eq!(
+                TransposingInstrument::from_name(&instrument.to_string()),
+                Some(instrument)
+            );
+        }
+        assert_eq!(
+            TransposingInstrument::from_name(" Horn in F"),
+            Some(TransposingInstrument::HornF)
+        );
+        assert_eq!(TransposingInstrument::from_name("kazoo"), None);
+    }
+}
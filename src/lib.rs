@@ -10,7 +10,16 @@
 )]
 #![doc = include_str!("../README.md")]
 
+pub mod acoustics;
+pub mod analysis;
+pub mod edo;
 pub mod harmony;
+pub mod instrument;
+pub mod midi;
+pub mod rhythm;
+pub mod staff;
+pub mod synth;
+pub mod tuning;
 
 /// returns a, b such that a*y + b = x and 0 <= b < y
 /// panics on y<=0, since this function is only needed for positive y
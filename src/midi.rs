@@ -0,0 +1,392 @@
+//! This module contains MIDI output for playing back tunings on ordinary synthesizers.
+//!
+//! Tunings can be sent to a synthesizer with the MIDI Tuning Standard (MTS), either as a bulk
+//! dump of all 128 keys or as real time changes of single keys.
+//! Synthesizers without MTS support can play tunings with MIDI Polyphonic Expression (MPE), where
+//! every note gets its own channel and is detuned with pitch bend, see [`MpeAllocator`].
+//!
+//! All functions return raw MIDI bytes, which can be written to files or sent to a MIDI port.
+use crate::{
+    harmony::ChromaticPitch,
+    tuning::{EqualTemperament, Tuning},
+};
+
+/// The frequency of MIDI key 0
+const KEY_0_FREQUENCY: f64 = 8.175_798_915_643_707;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A frequency in the format of the MIDI Tuning Standard: a MIDI key in twelve tone equal
+/// temperament with A4 = 440Hz and a 14 bit fraction of a semitone above it.
+///
+/// # Examples
+/// ```
+/// # use music_types::midi::MtsFrequency;
+/// let frequency = MtsFrequency::from_frequency(440.0).unwrap();
+/// assert_eq!(frequency.to_bytes(), [69, 0, 0]);
+/// // a quarter tone above A4
+/// let frequency = MtsFrequency::from_frequency(452.893).unwrap();
+/// assert_eq!(frequency.to_bytes(), [69, 64, 0]);
+/// ```
+pub struct MtsFrequency {
+    key: u8,
+    fraction: u16,
+}
+
+impl MtsFrequency {
+    /// the bytes which mark a key whose tuning should not change
+    pub const NO_CHANGE: [u8; 3] = [0x7f, 0x7f, 0x7f];
+
+    /// converts a frequency in Hz, rounding to the nearest 1/16384 of a semitone
+    ///
+    /// Returns `None` if the frequency is outside of the range of MIDI keys 0 to 127.
+    pub fn from_frequency(frequency: f64) -> Option<Self> {
+        let steps = (12.0 * 16384.0 * (frequency / KEY_0_FREQUENCY).log2()).round();
+        if !(0.0..128.0 * 16384.0).contains(&steps) {
+            return None;
+        }
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let steps = steps as u32;
+        // the highest value is reserved for no change
+        let steps = steps.min(128 * 16384 - 2);
+        Some(Self {
+            key: u8::try_from(steps / 16384).ok()?,
+            fraction: u16::try_from(steps % 16384).ok()?,
+        })
+    }
+
+    /// returns the MIDI key below the frequency
+    pub fn key(&self) -> u8 {
+        self.key
+    }
+
+    /// returns the distance from the key in units of 1/16384 of a semitone
+    pub fn fraction(&self) -> u16 {
+        self.fraction
+    }
+
+    /// returns the frequency in Hz
+    pub fn to_frequency(&self) -> f64 {
+        let semitones = f64::from(self.key) + f64::from(self.fraction) / 16384.0;
+        KEY_0_FREQUENCY * (semitones / 12.0).exp2()
+    }
+
+    /// returns the three data bytes of the frequency
+    pub fn to_bytes(&self) -> [u8; 3] {
+        let [msb, lsb] = split_14_bits(self.fraction);
+        [self.key, msb, lsb]
+    }
+}
+
+/// splits a 14 bit value into its most and least significant 7 bits
+fn split_14_bits(value: u16) -> [u8; 2] {
+    let value = value.min(0x3fff);
+    [(value >> 7) as u8, (value & 0x7f) as u8]
+}
+
+/// the MTS data bytes of the frequency of `pitch` in `tuning`
+fn mts_bytes(tuning: &(impl Tuning + ?Sized), pitch: ChromaticPitch) -> [u8; 3] {
+    MtsFrequency::from_frequency(tuning.chromatic_frequency(pitch))
+        .map_or(MtsFrequency::NO_CHANGE, |f| f.to_bytes())
+}
+
+/// Creates a MIDI Tuning Standard bulk dump, a non real time system exclusive message which retunes all
+/// 128 MIDI keys of the tuning program `program`.
+///
+/// Every key is tuned to the frequency of its [`ChromaticPitch`] in `tuning`.
+/// Keys whose frequency is out of range or not finite are marked as unchanged.
+/// The name is truncated or padded with spaces to 16 ASCII characters,
+/// other characters are replaced by `?`.
+///
+/// # Examples
+/// ```
+/// # use music_types::{midi::bulk_tuning_dump, tuning::EqualTemperament};
+/// let message = bulk_tuning_dump(&EqualTemperament::default(), 0x7f, 0, "12-TET");
+/// assert_eq!(message.len(), 408);
+/// assert_eq!(message[..6], [0xf0, 0x7e, 0x7f, 0x08, 0x01, 0x00]);
+/// // A4 is key 69 exactly
+/// assert_eq!(message[22 + 69 * 3..][..3], [69, 0, 0]);
+/// ```
+pub fn bulk_tuning_dump(
+    tuning: &(impl Tuning + ?Sized),
+    device_id: u8,
+    program: u8,
+    name: &str,
+) -> Vec<u8> {
+    let mut message = vec![0xf0, 0x7e, device_id & 0x7f, 0x08, 0x01, program & 0x7f];
+    let name = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii() && !c.is_ascii_control() {
+                c as u8
+            } else {
+                b'?'
+            }
+        })
+        .chain(std::iter::repeat(b' '))
+        .take(16);
+    message.extend(name);
+    for key in 0..=127 {
+        message.extend(mts_bytes(tuning, ChromaticPitch::from_midi_pitch(key)));
+    }
+    // the checksum is the xor of all bytes after the start of the message
+    let checksum = message[1..].iter().fold(0, |a, b| a ^ b) & 0x7f;
+    message.push(checksum);
+    message.push(0xf7);
+    message
+}
+
+/// Creates a MIDI Tuning Standard single note tuning change, a real time system exclusive message which
+/// retunes the keys of `pitches` in the tuning program `program`.
+///
+/// At most 127 keys can be changed with one message, further pitches are ignored,
+/// as are pitches outside of the MIDI range.
+///
+/// # Examples
+/// ```
+/// # use music_types::{harmony::ChromaticPitch, midi::single_note_tuning_change, tuning::EqualTemperament};
+/// let tuning = EqualTemperament::new(442.0);
+/// let message = single_note_tuning_change(&tuning, 0x7f, 0, &[ChromaticPitch::new(9)]);
+/// assert_eq!(message, vec![0xf0, 0x7f, 0x7f, 0x08, 0x02, 0x00, 1, 69, 69, 10, 6, 0xf7]);
+/// ```
+pub fn single_note_tuning_change(
+    tuning: &(impl Tuning + ?Sized),
+    device_id: u8,
+    program: u8,
+    pitches: &[ChromaticPitch],
+) -> Vec<u8> {
+    let changes: Vec<_> = pitches
+        .iter()
+        .filter_map(|p| Some((p.to_midi_pitch()?, mts_bytes(tuning, *p))))
+        .take(127)
+        .collect();
+    let mut message = vec![0xf0, 0x7f, device_id & 0x7f, 0x08, 0x02, program & 0x7f];
+    message.push(u8::try_from(changes.len()).unwrap_or(127));
+    for (key, bytes) in changes {
+        message.push(key);
+        message.extend(bytes);
+    }
+    message.push(0xf7);
+    message
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+struct Voice {
+    pitch: ChromaticPitch,
+    key: u8,
+    started: u64,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Allocates a MIDI channel to each sounding note of an MPE lower zone and detunes it with
+/// pitch bend.
+///
+/// Channel 1 is the master channel and the following channels are member channels.
+/// Notes are placed on the free member channel which was released first.
+/// If every member channel is in use, the oldest note is stopped and its channel is reused.
+///
+/// # Examples
+/// ```
+/// # use music_types::{harmony::ChromaticPitch, midi::MpeAllocator, tuning::EqualTemperament};
+/// let mut allocator = MpeAllocator::new(15, 48);
+/// let tuning = EqualTemperament::new(442.0);
+/// let bytes = allocator.note_on(ChromaticPitch::new(9), &tuning, 100);
+/// // pitch bend up by 7.85 cents on channel 2, then A4 on channel 2
+/// assert_eq!(bytes, vec![0xe1, 13, 64, 0x91, 69, 100]);
+/// assert_eq!(allocator.note_off(ChromaticPitch::new(9)), vec![0x81, 69, 0]);
+/// ```
+pub struct MpeAllocator {
+    voices: Vec<Option<Voice>>,
+    released: Vec<u64>,
+    bend_range: u8,
+    time: u64,
+}
+
+impl MpeAllocator {
+    /// creates an allocator for an MPE lower zone with `member_channels` member channels and a
+    /// pitch bend range of `bend_range` semitones
+    ///
+    /// # Panics
+    /// Panics if the number of member channels is not in 1..=15.
+    pub fn new(member_channels: u8, bend_range: u8) -> Self {
+        assert!(
+            (1..=15).contains(&member_channels),
+            "an MPE zone has between 1 and 15 member channels"
+        );
+        let channels = usize::from(member_channels);
+        Self {
+            voices: vec![None; channels],
+            released: vec![0; channels],
+            bend_range,
+            time: 0,
+        }
+    }
+
+    /// returns the messages which configure a synthesizer for this zone: the MPE configuration
+    /// message on the master channel and the pitch bend range on every member channel
+    pub fn configuration(&self) -> Vec<u8> {
+        let member_channels = u8::try_from(self.voices.len()).unwrap_or(15);
+        // registered parameter 6 is the MPE configuration message
+        let mut bytes = vec![
+            0xb0,
+            0x65,
+            0x00,
+            0xb0,
+            0x64,
+            0x06,
+            0xb0,
+            0x06,
+            member_channels,
+        ];
+        for channel in 1..=member_channels {
+            // registered parameter 0 is the pitch bend sensitivity
+            let status = 0xb0 | channel;
+            bytes.extend([status, 0x65, 0x00, status, 0x64, 0x00]);
+            bytes.extend([status, 0x06, self.bend_range, status, 0x26, 0x00]);
+        }
+        bytes
+    }
+
+    /// starts a note and returns its pitch bend and note on messages
+    ///
+    /// The note is played on the MIDI key nearest to the frequency of `pitch` in `tuning` and
+    /// bent to the exact frequency. If a channel has to be stolen, the note off message of the
+    /// stolen note comes first.
+    /// If the frequency is not finite or its MIDI key is out of range, nothing is played.
+    pub fn note_on(
+        &mut self,
+        pitch: ChromaticPitch,
+        tuning: &(impl Tuning + ?Sized),
+        velocity: u8,
+    ) -> Vec<u8> {
+        let frequency = tuning.chromatic_frequency(pitch);
+        let (nearest, cents) = EqualTemperament::default().nearest_chromatic(frequency);
+        let Some(key) = nearest.to_midi_pitch().filter(|_| cents.is_finite()) else {
+            return Vec::new();
+        };
+
+        let mut bytes = Vec::new();
+        let index = self.free_channel().unwrap_or_else(|| {
+            let oldest = self.oldest_channel();
+            bytes.extend(self.release(oldest));
+            oldest
+        });
+        self.time += 1;
+        self.voices[index] = Some(Voice {
+            pitch,
+            key,
+            started: self.time,
+        });
+
+        let channel = Self::channel(index);
+        let bend = (cents / 100.0 / f64::from(self.bend_range.max(1))).mul_add(8192.0, 8192.0);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let [msb, lsb] = split_14_bits(bend.round().clamp(0.0, 16383.0) as u16);
+        bytes.extend([0xe0 | channel, lsb, msb]);
+        bytes.extend([0x90 | channel, key, velocity & 0x7f]);
+        bytes
+    }
+
+    /// stops the oldest sounding note of `pitch` and returns its note off message
+    ///
+    /// Returns no bytes if `pitch` is not sounding.
+    pub fn note_off(&mut self, pitch: ChromaticPitch) -> Vec<u8> {
+        self.voices
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| v.filter(|v| v.pitch == pitch).map(|v| (i, v.started)))
+            .min_by_key(|(_, started)| *started)
+            .map_or_else(Vec::new, |(index, _)| self.release(index).to_vec())
+    }
+
+    /// returns the channel of `pitch` if it is sounding, where the master channel is 0
+    pub fn channel_of(&self, pitch: ChromaticPitch) -> Option<u8> {
+        self.voices
+            .iter()
+            .position(|v| v.is_some_and(|v| v.pitch == pitch))
+            .map(Self::channel)
+    }
+
+    fn channel(index: usize) -> u8 {
+        u8::try_from(index + 1).unwrap_or(15)
+    }
+
+    fn free_channel(&self) -> Option<usize> {
+        (0..self.voices.len())
+            .filter(|i| self.voices[*i].is_none())
+            .min_by_key(|i| self.released[*i])
+    }
+
+    fn oldest_channel(&self) -> usize {
+        (0..self.voices.len())
+            .min_by_key(|i| self.voices[*i].map_or(0, |v| v.started))
+            .unwrap_or(0)
+    }
+
+    fn release(&mut self, index: usize) -> [u8; 3] {
+        let key = self.voices[index].take().map_or(0, |v| v.key);
+        self.time += 1;
+        self.released[index] = self.time;
+        [0x80 | Self::channel(index), key, 0]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tuning::RegularTemperament;
+
+    #[test]
+    fn mts_frequency() {
+        for key in 0..=127 {
+            let frequency = ChromaticPitch::from_midi_pitch(key).to_frequency();
+            let mts = MtsFrequency::from_frequency(frequency.into()).unwrap();
+            assert!((mts.to_frequency() / f64::from(frequency) - 1.0).abs() < 1e-5);
+        }
+        assert_eq!(MtsFrequency::from_frequency(1.0), None);
+        assert_eq!(MtsFrequency::from_frequency(f64::NAN), None);
+        assert_eq!(MtsFrequency::from_frequency(20000.0), None);
+        // the highest frequency may not be confused with no change
+        let highest = MtsFrequency::from_frequency(13289.7).unwrap();
+        assert_ne!(highest.to_bytes(), MtsFrequency::NO_CHANGE);
+    }
+
+    #[test]
+    fn bulk_dump_checksum() {
+        let tuning = RegularTemperament::quarter_comma_meantone(440.0);
+        let message = bulk_tuning_dump(&tuning, 0, 3, "meantone");
+        assert_eq!(message.len(), 408);
+        assert_eq!(&message[6..22], b"meantone        ");
+        let checksum = message[1..406].iter().fold(0, |a, b| a ^ b);
+        assert_eq!(checksum & 0x7f, message[406]);
+        assert!(message[1..407].iter().all(|b| *b < 0x80));
+    }
+
+    #[test]
+    fn allocation() {
+        let tuning = EqualTemperament::default();
+        let mut allocator = MpeAllocator::new(2, 48);
+        let (c, e, g) = (
+            ChromaticPitch::new(0),
+            ChromaticPitch::new(4),
+            ChromaticPitch::new(7),
+        );
+        allocator.note_on(c, &tuning, 100);
+        allocator.note_on(e, &tuning, 100);
+        assert_eq!(allocator.channel_of(c), Some(1));
+        assert_eq!(allocator.channel_of(e), Some(2));
+
+        // the oldest note is stolen
+        let bytes = allocator.note_on(g, &tuning, 100);
+        assert_eq!(bytes[..3], [0x81, 60, 0]);
+        assert_eq!(allocator.channel_of(c), None);
+        assert_eq!(allocator.channel_of(g), Some(1));
+
+        // the channel released first is reused first
+        allocator.note_off(e);
+        allocator.note_off(g);
+        allocator.note_on(c, &tuning, 100);
+        assert_eq!(allocator.channel_of(c), Some(2));
+        assert!(allocator.note_off(e).is_empty());
+    }
+}
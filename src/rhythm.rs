@@ -0,0 +1,75 @@
+//! This module contains types representing rhythmic information
+//!
+//! Time is measured in beats, where one beat is usually a quarter note.
+
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A value placed in time, for example a note or a chord.
+///
+/// The onset and the duration are measured in beats.
+///
+/// # Examples
+/// ```
+/// # use music_types::{harmony::{Pitch, ParsePitchError}, rhythm::Timed};
+/// # use std::str::FromStr;
+/// let note = Timed::new(Pitch::from_str("C4")?, 2.0, 1.5);
+/// assert_eq!(note.end(), 3.5);
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub struct Timed<T> {
+    pub(crate) value: T,
+    pub(crate) onset: f64,
+    pub(crate) duration: f64,
+}
+
+impl<T> Timed<T> {
+    /// Creates a timed value starting at `onset` and lasting for `duration` beats
+    pub fn new(value: T, onset: f64, duration: f64) -> Self {
+        Self {
+            value,
+            onset,
+            duration,
+        }
+    }
+
+    /// returns a reference to the value
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// returns the value, consuming self
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
+    /// returns the onset in beats
+    pub fn onset(&self) -> f64 {
+        self.onset
+    }
+
+    /// returns the duration in beats
+    pub fn duration(&self) -> f64 {
+        self.duration
+    }
+
+    /// returns the time at which the value stops sounding
+    pub fn end(&self) -> f64 {
+        self.onset + self.duration
+    }
+
+    /// returns true if the value sounds at the given time
+    ///
+    /// The onset is included, the end is not.
+    pub fn sounds_at(&self, time: f64) -> bool {
+        self.onset <= time && time < self.end()
+    }
+
+    /// Applies `f` to the value, keeping the timing
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Timed<U> {
+        Timed {
+            value: f(self.value),
+            onset: self.onset,
+            duration: self.duration,
+        }
+    }
+}
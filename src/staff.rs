@@ -0,0 +1,304 @@
+//! This module contains types for placing pitches on a five line staff
+
+#[cfg(feature = "smufl")]
+mod svg;
+
+#[cfg(feature = "smufl")]
+pub use svg::{StaffItem, SvgStaff};
+
+use crate::{
+    div_remainder,
+    harmony::{Accidental, Pitch, PitchName, scale::KeySignature},
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The sign of a clef, which marks the line of a reference pitch
+pub enum ClefSign {
+    /// The G clef, marking G4
+    G,
+    /// The F clef, marking F3
+    F,
+    /// The C clef, marking C4
+    C,
+    /// The percussion clef, which places pitches like a treble clef
+    Percussion,
+}
+
+impl ClefSign {
+    /// returns the pitch marked by the sign, for the percussion clef the pitch of the G clef
+    pub fn pitch(self) -> Pitch {
+        match self {
+            Self::G | Self::Percussion => Pitch::new(4, 7),
+            Self::F => Pitch::new(-4, -7),
+            Self::C => Pitch::new(0, 0),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A place on the staff, either a line or a space
+///
+/// Lines are numbered from the bottom line, which is line 1, to the top line, which is line 5.
+/// Ledger lines continue the numbering, so the first ledger line below the staff is line 0 and
+/// the first ledger line above is line 6. A space is numbered after the line below it.
+pub enum StaffPlace {
+    /// A line
+    Line(i16),
+    /// The space above the line with the same number
+    Space(i16),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A clef on a five line staff
+///
+/// A clef places the pitch of its sign on one of the lines of the staff. Octave clefs shift all
+/// pitches by octaves, for example the G clef with an 8 below shows pitches an octave higher
+/// than they sound.
+///
+/// Positions on the staff are measured in steps above the bottom line, so lines are at even
+/// steps from 0 to 8 and spaces are at odd steps.
+///
+/// # Examples
+/// ```
+/// # use music_types::{harmony::{Pitch, ParsePitchError}, staff::{Clef, StaffPlace}};
+/// # use std::str::FromStr;
+/// let c4 = Pitch::from_str("C4")?;
+/// assert_eq!(Clef::TREBLE.place(c4), StaffPlace::Line(0));
+/// assert_eq!(Clef::TREBLE.ledger_lines(c4), 1);
+/// assert_eq!(Clef::ALTO.place(c4), StaffPlace::Line(3));
+/// assert_eq!(Clef::BASS.place(c4), StaffPlace::Line(6));
+/// assert_eq!(Clef::TREBLE_8VB.place(c4), StaffPlace::Space(3));
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub struct Clef {
+    sign: ClefSign,
+    line: i16,
+    octave: i16,
+}
+
+impl Default for Clef {
+    fn default() -> Self {
+        Self::TREBLE
+    }
+}
+
+impl Clef {
+    /// The treble clef, a G clef on the second line
+    pub const TREBLE: Self = Self::new(ClefSign::G, 2, 0);
+    /// The treble clef sounding an octave lower, used for tenor voices and guitar
+    pub const TREBLE_8VB: Self = Self::new(ClefSign::G, 2, -1);
+    /// The treble clef sounding an octave higher
+    pub const TREBLE_8VA: Self = Self::new(ClefSign::G, 2, 1);
+    /// The french violin clef, a G clef on the first line
+    pub const FRENCH_VIOLIN: Self = Self::new(ClefSign::G, 1, 0);
+    /// The bass clef, an F clef on the fourth line
+    pub const BASS: Self = Self::new(ClefSign::F, 4, 0);
+    /// The bass clef sounding an octave lower
+    pub const BASS_8VB: Self = Self::new(ClefSign::F, 4, -1);
+    /// The baritone clef, an F clef on the third line
+    pub const BARITONE: Self = Self::new(ClefSign::F, 3, 0);
+    /// The soprano clef, a C clef on the first line
+    pub const SOPRANO: Self = Self::new(ClefSign::C, 1, 0);
+    /// The mezzo-soprano clef, a C clef on the second line
+    pub const MEZZO_SOPRANO: Self = Self::new(ClefSign::C, 2, 0);
+    /// The alto clef, a C clef on the third line
+    pub const ALTO: Self = Self::new(ClefSign::C, 3, 0);
+    /// The tenor clef, a C clef on the fourth line
+    pub const TENOR: Self = Self::new(ClefSign::C, 4, 0);
+    /// The percussion clef, which places pitches like the treble clef and is drawn centered on
+    /// the staff
+    pub const PERCUSSION: Self = Self::new(ClefSign::Percussion, 2, 0);
+
+    /// Creates a clef with `sign` on `line`, where pitches sound `octave` octaves higher than
+    /// written.
+    ///
+    /// The line of the percussion sign only places the pitches, the sign itself is centered on
+    /// the staff.
+    pub const fn new(sign: ClefSign, line: i16, octave: i16) -> Self {
+        Self { sign, line, octave }
+    }
+
+    /// returns the sign of the clef
+    pub fn sign(&self) -> ClefSign {
+        self.sign
+    }
+
+    /// returns the line of the sign, counted from the bottom line
+    pub fn line(&self) -> i16 {
+        self.line
+    }
+
+    /// returns the octave shift, negative if the pitches sound lower than written
+    pub fn octave(&self) -> i16 {
+        self.octave
+    }
+
+    /// returns the step above the bottom line at which `pitch` is written
+    pub fn step(&self, pitch: Pitch) -> i16 {
+        let reference = self.sign.pitch().staff_position() + 7 * self.octave;
+        pitch.staff_position() - reference + 2 * (self.line - 1)
+    }
+
+    /// returns the natural pitch written at `step` above the bottom line
+    pub fn pitch_at(&self, step: i16) -> Pitch {
+        let reference = self.sign.pitch().staff_position() + 7 * self.octave;
+        let diatonic = step + reference - 2 * (self.line - 1);
+        let (octave, note) = div_remainder(diatonic, 7);
+        let name = PitchName::from_diatonic_steps(note);
+        Pitch::new(diatonic, 12 * octave + name.to_chromatic_steps())
+    }
+
+    /// returns the line or space at which `pitch` is written
+    pub fn place(&self, pitch: Pitch) -> StaffPlace {
+        let (line, space) = div_remainder(self.step(pitch), 2);
+        if space == 0 {
+            StaffPlace::Line(line + 1)
+        } else {
+            StaffPlace::Space(line + 1)
+        }
+    }
+
+    /// returns the number of ledger lines needed to write `pitch` above or below the staff
+    pub fn ledger_lines(&self, pitch: Pitch) -> u16 {
+        let step = self.step(pitch);
+        let outside = if step < 0 { -step } else { step - 8 };
+        outside.max(0).unsigned_abs() / 2
+    }
+
+    /// Returns the steps above the bottom line and the accidentals of `key` in the order they
+    /// are written after the clef.
+    ///
+    /// The accidentals follow the usual pattern of the treble clef, shifted to the clef. The
+    /// sharps are written lower if the pattern would reach above the staff, as in the tenor
+    /// clef.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::{harmony::{scale::KeySignature, Pitch, ParsePitchError}, staff::Clef};
+    /// let a_major = KeySignature::major(Pitch::class_from_str("A")?);
+    /// let steps = |clef: Clef| -> Vec<i16> {
+    ///     clef.key_signature_steps(&a_major).iter().map(|(step, _)| *step).collect()
+    /// };
+    /// assert_eq!(steps(Clef::TREBLE), [8, 5, 9]);
+    /// assert_eq!(steps(Clef::BASS), [6, 3, 7]);
+    /// assert_eq!(steps(Clef::TENOR), [2, 6, 3]);
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    pub fn key_signature_steps(&self, key: &KeySignature) -> Vec<(i16, Accidental)> {
+        // the offset of the clef to the treble clef, within a fourth up or down
+        let shift = (self.step(Clef::TREBLE.pitch_at(2)) - 2).rem_euclid(7);
+        let shift = if shift > 3 { shift - 7 } else { shift };
+        key.accidentals()
+            .into_iter()
+            .map(|pitch| {
+                let accidental = pitch.accidental();
+                // the lowest step of the pattern in the treble clef
+                let mut lowest = if accidental.chromatic_shift() > 0 {
+                    3
+                } else {
+                    1
+                } + shift;
+                if lowest + 6 > 9 {
+                    lowest -= 2;
+                }
+                let step = lowest + (self.step(pitch) - lowest).rem_euclid(7);
+                (step, accidental)
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "smufl")]
+impl Clef {
+    /// returns the corresponding smufl glyph upto two octaves of shift
+    ///
+    /// The C clef only has a glyph for the shift an octave down.
+    pub fn to_smufl(&self) -> Option<smufl::Glyph> {
+        use smufl::Glyph::*;
+
+        match (self.sign, self.octave) {
+            (ClefSign::G, -2) => Some(GClef15Mb),
+            (ClefSign::G, -1) => Some(GClef8Vb),
+            (ClefSign::G, 0) => Some(GClef),
+            (ClefSign::G, 1) => Some(GClef8Va),
+            (ClefSign::G, 2) => Some(GClef15Ma),
+            (ClefSign::F, -2) => Some(FClef15Mb),
+            (ClefSign::F, -1) => Some(FClef8Vb),
+            (ClefSign::F, 0) => Some(FClef),
+            (ClefSign::F, 1) => Some(FClef8Va),
+            (ClefSign::F, 2) => Some(FClef15Ma),
+            (ClefSign::C, -1) => Some(CClef8Vb),
+            (ClefSign::C, 0) => Some(CClef),
+            (ClefSign::Percussion, _) => Some(UnpitchedPercussionClef1),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn pitch(s: &str) -> Pitch {
+        Pitch::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn lines() {
+        let lines = |clef: Clef| -> Vec<String> {
+            (0..5)
+                .map(|line| clef.pitch_at(2 * line).to_string())
+                .collect()
+        };
+        assert_eq!(lines(Clef::TREBLE), ["E4", "G4", "B4", "D5", "F5"]);
+        assert_eq!(lines(Clef::BASS), ["G2", "B2", "D3", "F3", "A3"]);
+        assert_eq!(lines(Clef::ALTO), ["F3", "A3", "C4", "E4", "G4"]);
+        assert_eq!(lines(Clef::TENOR), ["D3", "F3", "A3", "C4", "E4"]);
+        assert_eq!(lines(Clef::TREBLE_8VB), ["E3", "G3", "B3", "D4", "F4"]);
+        assert_eq!(lines(Clef::FRENCH_VIOLIN), ["G4", "B4", "D5", "F5", "A5"]);
+        assert_eq!(lines(Clef::PERCUSSION), lines(Clef::TREBLE));
+        assert_eq!(Clef::BASS.pitch_at(-4).to_string(), "C2");
+    }
+
+    #[test]
+    fn places() {
+        for clef in [Clef::TREBLE, Clef::BASS_8VB, Clef::SOPRANO, Clef::BARITONE] {
+            for step in -10..20 {
+                assert_eq!(clef.step(clef.pitch_at(step)), step);
+            }
+        }
+        // accidentals do not change the place
+        assert_eq!(Clef::TREBLE.place(pitch("F#5")), StaffPlace::Line(5));
+        assert_eq!(Clef::TREBLE.place(pitch("Fb5")), StaffPlace::Line(5));
+        assert_eq!(Clef::TREBLE.place(pitch("D4")), StaffPlace::Space(0));
+        assert_eq!(Clef::TREBLE.place(pitch("G5")), StaffPlace::Space(5));
+        assert_eq!(Clef::BASS.place(pitch("E2")), StaffPlace::Line(0));
+        assert_eq!(Clef::BASS.place(pitch("D2")), StaffPlace::Space(-1));
+    }
+
+    #[test]
+    fn ledger_lines() {
+        let treble = Clef::TREBLE;
+        let counts: Vec<_> = ["A3", "B3", "C4", "D4", "E4", "F5", "G5", "A5", "B5", "C6"]
+            .iter()
+            .map(|s| treble.ledger_lines(pitch(s)))
+            .collect();
+        assert_eq!(counts, [2, 1, 1, 0, 0, 0, 0, 1, 1, 2]);
+        assert_eq!(Clef::BASS.ledger_lines(pitch("C4")), 1);
+        assert_eq!(Clef::MEZZO_SOPRANO.ledger_lines(pitch("C4")), 0);
+    }
+
+    #[cfg(feature = "smufl")]
+    #[test]
+    fn smufl() {
+        assert_eq!(Clef::TREBLE.to_smufl(), Some(smufl::Glyph::GClef));
+        assert_eq!(Clef::TREBLE_8VB.to_smufl(), Some(smufl::Glyph::GClef8Vb));
+        assert_eq!(Clef::TENOR.to_smufl(), Some(smufl::Glyph::CClef));
+        assert_eq!(Clef::new(ClefSign::C, 3, 1).to_smufl(), None);
+    }
+}
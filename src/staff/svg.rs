@@ -0,0 +1,366 @@
+use std::fmt::Write;
+
+use smufl::Glyph;
+
+use crate::harmony::{
+    Pitch,
+    scale::{AccidentalCalulator, KeySignature},
+};
+
+use super::{Clef, ClefSign};
+
+/// width of a whole notehead in staff spaces
+const NOTEHEAD_WIDTH: f64 = 1.7;
+/// width of an accidental including its padding in staff spaces
+const ACCIDENTAL_WIDTH: f64 = 1.2;
+/// extension of ledger lines beyond the notehead in staff spaces
+const LEDGER_EXTENSION: f64 = 0.4;
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+/// An item written on a staff by [`SvgStaff`]
+pub enum StaffItem {
+    /// Pitches sounding together, a single pitch for a note
+    Chord(Vec<Pitch>),
+    /// A bar line, which cancels the accidentals of the previous bar
+    BarLine,
+}
+
+impl From<Pitch> for StaffItem {
+    fn from(pitch: Pitch) -> Self {
+        Self::Chord(vec![pitch])
+    }
+}
+
+/// An element of the drawing, positioned in staff spaces horizontally and in steps above the
+/// bottom line vertically
+#[derive(Debug)]
+enum Element {
+    Glyph { x: f64, step: i16, glyph: Glyph },
+    Ledger { from: f64, to: f64, step: i16 },
+    BarLine { x: f64 },
+}
+
+#[derive(Clone, Debug)]
+/// Renders a staff with a clef, a key signature and notes to a self-contained SVG string
+///
+/// Glyphs are written as text in a smufl font referenced by name, Bravura by default, so the
+/// font has to be available where the SVG is shown. Notes are written as whole notes with the
+/// accidentals given by [`AccidentalCalulator`]. Accidentals without a smufl glyph are left out.
+///
+/// # Examples
+/// ```
+/// # use music_types::{harmony::{scale::KeySignature, Pitch, ParsePitchError}, staff::{Clef, StaffItem, SvgStaff}};
+/// # use std::str::FromStr;
+/// let staff = SvgStaff::new(Clef::BASS)
+///     .with_key_signature(KeySignature::major(Pitch::class_from_str("F")?))
+///     .with_staff_space(8.0);
+/// let chord = ["F2", "A2", "C3"].map(|s| Pitch::from_str(s)).into_iter().collect::<Result<_, _>>()?;
+/// let svg = staff.render(&[StaffItem::Chord(chord), StaffItem::BarLine, Pitch::from_str("B2")?.into()]);
+/// assert!(svg.starts_with("<svg"));
+/// assert!(svg.contains("font-family=\"Bravura\""));
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub struct SvgStaff {
+    clef: Clef,
+    key_signature: KeySignature,
+    font: String,
+    staff_space: f64,
+}
+
+impl SvgStaff {
+    /// creates a staff with `clef`, no key signature, the Bravura font and a staff space of 10
+    pub fn new(clef: Clef) -> Self {
+        Self {
+            clef,
+            key_signature: KeySignature::default(),
+            font: "Bravura".to_string(),
+            staff_space: 10.0,
+        }
+    }
+
+    /// sets the key signature
+    pub fn with_key_signature(mut self, key_signature: KeySignature) -> Self {
+        self.key_signature = key_signature;
+        self
+    }
+
+    /// sets the name of the smufl font
+    pub fn with_font(mut self, font: impl Into<String>) -> Self {
+        self.font = font.into();
+        self
+    }
+
+    /// sets the distance between two staff lines in SVG units
+    pub fn with_staff_space(mut self, staff_space: f64) -> Self {
+        self.staff_space = staff_space;
+        self
+    }
+
+    /// returns the clef
+    pub fn clef(&self) -> Clef {
+        self.clef
+    }
+
+    /// returns the key signature
+    pub fn key_signature(&self) -> &KeySignature {
+        &self.key_signature
+    }
+
+    /// Lays out the clef, the key signature and the items.
+    ///
+    /// Returns the elements and the width of the staff in staff spaces.
+    fn layout(&self, items: &[StaffItem]) -> (Vec<Element>, f64) {
+        let mut elements = Vec::new();
+        let mut x = 1.0;
+
+        if let Some(glyph) = self.clef.to_smufl() {
+            let step = if self.clef.sign() == ClefSign::Percussion {
+                4
+            } else {
+                2 * (self.clef.line() - 1)
+            };
+            elements.push(Element::Glyph { x, step, glyph });
+        }
+        x += 3.5;
+
+        for (step, accidental) in self.clef.key_signature_steps(&self.key_signature) {
+            if let Some(glyph) = accidental.to_smufl() {
+                elements.push(Element::Glyph { x, step, glyph });
+                x += ACCIDENTAL_WIDTH;
+            }
+        }
+        x += 1.0;
+
+        let mut calculator = AccidentalCalulator::from_key_signature(self.key_signature.clone());
+        for item in items {
+            match item {
+                StaffItem::Chord(pitches) => {
+                    let mut pitches = pitches.clone();
+                    pitches.sort_by_key(|pitch| self.clef.step(*pitch));
+                    x = self.layout_chord(&pitches, &mut calculator, x, &mut elements);
+                }
+                StaffItem::BarLine => {
+                    calculator.clear();
+                    elements.push(Element::BarLine { x });
+                    x += 1.5;
+                }
+            }
+        }
+        (elements, x)
+    }
+
+    /// Lays out the chord starting at `x` and returns the start of the next item.
+    ///
+    /// `pitches` are sorted from the lowest step.
+    fn layout_chord(
+        &self,
+        pitches: &[Pitch],
+        calculator: &mut AccidentalCalulator,
+        x: f64,
+        elements: &mut Vec<Element>,
+    ) -> f64 {
+        let steps: Vec<_> = pitches.iter().map(|pitch| self.clef.step(*pitch)).collect();
+        let accidentals: Vec<_> = pitches
+            .iter()
+            .map(|pitch| calculator.get_and_update(*pitch))
+            .collect();
+
+        // accidentals are stacked in columns from the top, a new column starts if two
+        // accidentals are closer than a sixth
+        let mut columns: Vec<i16> = Vec::new();
+        let mut placed = Vec::new();
+        for (step, accidental) in steps.iter().zip(&accidentals).rev() {
+            let Some(glyph) = accidental.and_then(|accidental| accidental.to_smufl()) else {
+                continue;
+            };
+            let column = columns
+                .iter()
+                .position(|last| last - step >= 6)
+                .unwrap_or(columns.len());
+            if column == columns.len() {
+                columns.push(*step);
+            }
+            columns[column] = *step;
+            placed.push((column, *step, glyph));
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let column_count = columns.len() as f64;
+        for (column, step, glyph) in placed {
+            #[allow(clippy::cast_precision_loss)]
+            let x = (column_count - 1.0 - column as f64).mul_add(ACCIDENTAL_WIDTH, x);
+            elements.push(Element::Glyph { x, step, glyph });
+        }
+
+        // the upper note of a second is written right of the lower note
+        let head = column_count.mul_add(ACCIDENTAL_WIDTH, x);
+        let mut displaced = false;
+        let mut width = NOTEHEAD_WIDTH;
+        for (i, step) in steps.iter().enumerate() {
+            displaced = i > 0 && step - steps[i - 1] == 1 && !displaced;
+            let x = if displaced {
+                width = 2.0 * NOTEHEAD_WIDTH;
+                head + NOTEHEAD_WIDTH
+            } else {
+                head
+            };
+            elements.push(Element::Glyph {
+                x,
+                step: *step,
+                glyph: Glyph::NoteheadWhole,
+            });
+        }
+
+        let (from, to) = (head - LEDGER_EXTENSION, head + width + LEDGER_EXTENSION);
+        let lowest = steps.first().copied().unwrap_or(0);
+        let highest = steps.last().copied().unwrap_or(0);
+        let ledgers = (lowest..=-2)
+            .chain(10..=highest)
+            .filter(|step| step % 2 == 0);
+        elements.extend(ledgers.map(|step| Element::Ledger { from, to, step }));
+        head + width + 2.0
+    }
+
+    /// Renders the staff with `items` to an SVG string.
+    pub fn render(&self, items: &[StaffItem]) -> String {
+        let (elements, width) = self.layout(items);
+        let steps = elements.iter().filter_map(|element| match element {
+            Element::Glyph { step, .. } | Element::Ledger { step, .. } => Some(*step),
+            Element::BarLine { .. } => None,
+        });
+        // leave room for the clef and the noteheads
+        let top = steps.clone().map(|step| step + 2).fold(12, i16::max);
+        let bottom = steps.map(|step| step - 2).fold(-4, i16::min);
+
+        let space = self.staff_space;
+        let y = |step: i16| f64::from(top - step) * space / 2.0;
+        let (width, height) = (width * space, y(bottom));
+        let mut svg = String::new();
+        // writing to a string does not fail
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width:.2}\" height=\"{height:.2}\" \
+             viewBox=\"0 0 {width:.2} {height:.2}\">"
+        );
+        let _ = writeln!(svg, "<g stroke=\"black\">");
+        let mut line = |x1: f64, x2: f64, y1: f64, y2: f64, thickness: f64| {
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{x1:.2}\" y1=\"{y1:.2}\" x2=\"{x2:.2}\" y2=\"{y2:.2}\" \
+                 stroke-width=\"{:.2}\"/>",
+                thickness * space
+            );
+        };
+        for step in (0..=8).step_by(2) {
+            line(0.0, width, y(step), y(step), 0.13);
+        }
+        for element in &elements {
+            match element {
+                Element::Ledger { from, to, step } => {
+                    line(from * space, to * space, y(*step), y(*step), 0.16);
+                }
+                Element::BarLine { x } => line(x * space, x * space, y(0), y(8), 0.16),
+                Element::Glyph { .. } => {}
+            }
+        }
+        let _ = writeln!(svg, "</g>");
+        let _ = writeln!(
+            svg,
+            "<g font-family=\"{}\" font-size=\"{:.2}\">",
+            escape(&self.font),
+            4.0 * space
+        );
+        for element in &elements {
+            if let Element::Glyph { x, step, glyph } = element {
+                let _ = writeln!(
+                    svg,
+                    "<text x=\"{:.2}\" y=\"{:.2}\">&#x{:X};</text>",
+                    x * space,
+                    y(*step),
+                    u32::from(glyph.codepoint())
+                );
+            }
+        }
+        let _ = writeln!(svg, "</g>");
+        let _ = writeln!(svg, "</svg>");
+        svg
+    }
+}
+
+/// escapes the characters of `text` which are not allowed in an attribute value
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn pitch(s: &str) -> Pitch {
+        Pitch::from_str(s).unwrap()
+    }
+
+    fn count(svg: &str, glyph: Glyph) -> usize {
+        let entity = format!("&#x{:X};", u32::from(glyph.codepoint()));
+        svg.matches(&entity).count()
+    }
+
+    #[test]
+    fn accidentals() {
+        let d_major = KeySignature::major(Pitch::class_from_str("D").unwrap());
+        let staff = SvgStaff::new(Clef::TREBLE).with_key_signature(d_major);
+        let items = [
+            pitch("F#4").into(),
+            pitch("F4").into(),
+            pitch("F4").into(),
+            StaffItem::BarLine,
+            pitch("F4").into(),
+            pitch("Bb4").into(),
+        ];
+        let svg = staff.render(&items);
+        assert_eq!(count(&svg, Glyph::GClef), 1);
+        assert_eq!(count(&svg, Glyph::AccidentalSharp), 2);
+        assert_eq!(count(&svg, Glyph::AccidentalNatural), 2);
+        assert_eq!(count(&svg, Glyph::AccidentalFlat), 1);
+        assert_eq!(count(&svg, Glyph::NoteheadWhole), 5);
+        // five staff lines and one bar line
+        assert_eq!(svg.matches("<line").count(), 6);
+    }
+
+    #[test]
+    fn chords_and_ledger_lines() {
+        let staff = SvgStaff::new(Clef::TREBLE).with_font("Petaluma & Co");
+        let chord = StaffItem::Chord(vec![pitch("A3"), pitch("C4"), pitch("D4"), pitch("C#6")]);
+        let svg = staff.render(&[chord]);
+        assert!(svg.contains("font-family=\"Petaluma &amp; Co\""));
+        assert_eq!(count(&svg, Glyph::NoteheadWhole), 4);
+        assert_eq!(count(&svg, Glyph::AccidentalSharp), 1);
+        // two ledger lines below and two above the staff
+        assert_eq!(svg.matches("<line").count(), 9);
+
+        let (elements, _) = staff.layout(&[StaffItem::Chord(vec![pitch("E4"), pitch("F4")])]);
+        let heads: Vec<_> = elements
+            .iter()
+            .filter_map(|element| match element {
+                Element::Glyph {
+                    x,
+                    glyph: Glyph::NoteheadWhole,
+                    ..
+                } => Some(*x),
+                _ => None,
+            })
+            .collect();
+        // the F is displaced to the right of the E
+        assert!((heads[1] - heads[0] - NOTEHEAD_WIDTH).abs() < 1e-9);
+    }
+
+    #[test]
+    fn percussion() {
+        let svg = SvgStaff::new(Clef::PERCUSSION).render(&[]);
+        assert_eq!(count(&svg, Glyph::UnpitchedPercussionClef1), 1);
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
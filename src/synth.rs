@@ -0,0 +1,379 @@
+//! This module contains a small additive synthesizer, which renders timed pitches to audio
+//! samples and WAV files without an audio device.
+use std::{
+    f64::consts::{PI, TAU},
+    io::{self, Write},
+};
+
+use crate::{harmony::Pitch, rhythm::Timed};
+
+/// The largest number of partials of a sawtooth or square wave, which bounds the cost of very
+/// low notes
+const MAX_PARTIALS: f64 = 128.0;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The waveform of an oscillator
+///
+/// Sawtooth and square waves are built from sine partials below the Nyquist frequency,
+/// so they do not alias. At most 128 partials are used.
+pub enum Waveform {
+    /// A pure sine tone
+    #[default]
+    Sine,
+    /// A sawtooth wave, containing all partials with amplitude `1/n`
+    Sawtooth,
+    /// A square wave, containing the odd partials with amplitude `1/n`
+    Square,
+}
+
+impl Waveform {
+    /// returns the value of the waveform at `phase` (in periods) for the given fundamental
+    fn sample(self, phase: f64, frequency: f64, sample_rate: f64) -> f64 {
+        let partials = (sample_rate / 2.0 / frequency)
+            .floor()
+            .clamp(1.0, MAX_PARTIALS);
+        let sum = |step: usize| {
+            (1..)
+                .step_by(step)
+                .take_while(|n| f64::from(*n) <= partials)
+                .map(|n: u32| (TAU * f64::from(n) * phase).sin() / f64::from(n))
+                .sum::<f64>()
+        };
+        match self {
+            Self::Sine => (TAU * phase).sin(),
+            Self::Sawtooth => 2.0 / PI * sum(1),
+            Self::Square => 4.0 / PI * sum(2),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An ADSR envelope
+///
+/// The attack, decay and release times are in seconds, the sustain level is relative to the
+/// peak. The release starts at the end of the note, so notes ring on for the release time.
+///
+/// # Examples
+/// ```
+/// # use music_types::synth::Envelope;
+/// let envelope = Envelope::new(0.125, 0.125, 0.5, 0.25);
+/// assert_eq!(envelope.amplitude(0.0625, 1.0), 0.5);
+/// assert_eq!(envelope.amplitude(0.125, 1.0), 1.0);
+/// assert_eq!(envelope.amplitude(0.5, 1.0), 0.5);
+/// assert_eq!(envelope.amplitude(1.125, 1.0), 0.25);
+/// assert_eq!(envelope.amplitude(1.25, 1.0), 0.0);
+/// ```
+pub struct Envelope {
+    attack: f64,
+    decay: f64,
+    sustain: f64,
+    release: f64,
+}
+
+impl Envelope {
+    /// creates an envelope, negative times are treated as zero and the sustain level is clamped
+    /// to 0.0..=1.0
+    pub fn new(attack: f64, decay: f64, sustain: f64, release: f64) -> Self {
+        Self {
+            attack: attack.max(0.0),
+            decay: decay.max(0.0),
+            sustain: sustain.clamp(0.0, 1.0),
+            release: release.max(0.0),
+        }
+    }
+
+    /// returns the release time in seconds
+    pub fn release(&self) -> f64 {
+        self.release
+    }
+
+    /// returns the amplitude `time` seconds after the start of a note which is held for
+    /// `duration` seconds
+    pub fn amplitude(&self, time: f64, duration: f64) -> f64 {
+        if time < 0.0 {
+            0.0
+        } else if time >= duration {
+            let level = self.amplitude(duration.min(time), f64::INFINITY);
+            if self.release == 0.0 {
+                return 0.0;
+            }
+            level * (1.0 - (time - duration) / self.release).max(0.0)
+        } else if time < self.attack {
+            time / self.attack
+        } else if time < self.attack + self.decay {
+            1.0 - (1.0 - self.sustain) * (time - self.attack) / self.decay
+        } else {
+            self.sustain
+        }
+    }
+}
+
+impl Default for Envelope {
+    fn default() -> Self {
+        Self::new(0.01, 0.1, 0.7, 0.1)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Renders timed pitches to mono audio
+///
+/// Onsets and durations of the notes are in beats, which are converted to seconds with the
+/// tempo. Frequencies are taken from [`Pitch::to_frequency_tuning`].
+/// The default synthesizer renders sine tones at 44100 Hz and 120 beats per minute with
+/// A4 = 440 Hz.
+///
+/// # Examples
+/// ```
+/// # use music_types::{harmony::{Pitch, ParsePitchError}, rhythm::Timed, synth::{Synth, Waveform}};
+/// # use std::str::FromStr;
+/// let notes = vec![
+///     Timed::new(Pitch::from_str("C4")?, 0.0, 1.0),
+///     Timed::new(Pitch::from_str("E4")?, 1.0, 1.0),
+/// ];
+/// let synth = Synth::default().with_waveform(Waveform::Square);
+/// let mut wav = Vec::new();
+/// synth.write_wav(&notes, &mut wav).unwrap();
+/// assert_eq!(&wav[..4], b"RIFF");
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub struct Synth {
+    sample_rate: u32,
+    tempo: f64,
+    a_4: f32,
+    gain: f64,
+    waveform: Waveform,
+    envelope: Envelope,
+}
+
+impl Default for Synth {
+    fn default() -> Self {
+        Self {
+            sample_rate: 44100,
+            tempo: 120.0,
+            a_4: 440.0,
+            gain: 0.5,
+            waveform: Waveform::default(),
+            envelope: Envelope::default(),
+        }
+    }
+}
+
+impl Synth {
+    /// sets the sample rate in Hz
+    ///
+    /// # Panics
+    /// Panics if the sample rate is zero or too high for a WAV file, above `u32::MAX / 2`.
+    pub fn with_sample_rate(mut self, sample_rate: u32) -> Self {
+        assert!(
+            sample_rate > 0 && sample_rate <= u32::MAX / 2,
+            "the sample rate must be positive and at most u32::MAX / 2"
+        );
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    /// sets the tempo in beats per minute
+    ///
+    /// # Panics
+    /// Panics if the tempo is not finite and positive.
+    pub fn with_tempo(mut self, tempo: f64) -> Self {
+        assert!(
+            tempo.is_finite() && tempo > 0.0,
+            "the tempo must be finite and positive"
+        );
+        self.tempo = tempo;
+        self
+    }
+
+    /// sets the frequency of A4 in Hz
+    ///
+    /// # Panics
+    /// Panics if the frequency is not finite and positive.
+    pub fn with_tuning(mut self, a_4: f32) -> Self {
+        assert!(
+            a_4.is_finite() && a_4 > 0.0,
+            "the frequency of A4 must be finite and positive"
+        );
+        self.a_4 = a_4;
+        self
+    }
+
+    /// sets the peak amplitude of a single note
+    pub fn with_gain(mut self, gain: f64) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    /// sets the waveform of the oscillator
+    pub fn with_waveform(mut self, waveform: Waveform) -> Self {
+        self.waveform = waveform;
+        self
+    }
+
+    /// sets the envelope of every note
+    pub fn with_envelope(mut self, envelope: Envelope) -> Self {
+        self.envelope = envelope;
+        self
+    }
+
+    /// returns the sample rate in Hz
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Renders the notes to samples starting at beat 0.
+    ///
+    /// The samples are scaled down if the mix would exceed the range -1.0..=1.0.
+    pub fn render(&self, notes: &[Timed<Pitch>]) -> Vec<f64> {
+        let sample_rate = f64::from(self.sample_rate);
+        let seconds = |beats: f64| beats * 60.0 / self.tempo;
+        let length = notes
+            .iter()
+            .map(|n| seconds(n.end()) + self.envelope.release)
+            .fold(0.0, f64::max);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let mut samples = vec![0.0; (length * sample_rate).round() as usize];
+
+        for note in notes {
+            let frequency = f64::from(note.value.to_frequency_tuning(self.a_4));
+            let (onset, duration) = (seconds(note.onset.max(0.0)), seconds(note.duration));
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let first = (onset * sample_rate).ceil() as usize;
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let last = ((onset + duration + self.envelope.release) * sample_rate).round() as usize;
+            for (i, sample) in samples.iter_mut().enumerate().take(last).skip(first) {
+                #[allow(clippy::cast_precision_loss)]
+                let time = i as f64 / sample_rate - onset;
+                let amplitude = self.envelope.amplitude(time, duration);
+                *sample += self.gain
+                    * amplitude
+                    * self
+                        .waveform
+                        .sample((time * frequency).fract(), frequency, sample_rate);
+            }
+        }
+
+        let peak = samples.iter().map(|s| s.abs()).fold(1.0, f64::max);
+        for sample in &mut samples {
+            *sample /= peak;
+        }
+        samples
+    }
+
+    /// Renders the notes and writes them as a 16 bit mono WAV file.
+    ///
+    /// # Errors
+    /// Returns an error if writing fails or if the audio is too long for a WAV file.
+    pub fn write_wav(&self, notes: &[Timed<Pitch>], mut writer: impl Write) -> io::Result<()> {
+        let samples = self.render(notes);
+        let data_length = u32::try_from(samples.len() * 2)
+            .ok()
+            .filter(|l| *l <= u32::MAX - 36)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "audio too long"))?;
+
+        writer.write_all(b"RIFF")?;
+        writer.write_all(&(36 + data_length).to_le_bytes())?;
+        writer.write_all(b"WAVE")?;
+        writer.write_all(b"fmt ")?;
+        writer.write_all(&16_u32.to_le_bytes())?;
+        // PCM, one channel
+        writer.write_all(&1_u16.to_le_bytes())?;
+        writer.write_all(&1_u16.to_le_bytes())?;
+        writer.write_all(&self.sample_rate.to_le_bytes())?;
+        writer.write_all(&(self.sample_rate * 2).to_le_bytes())?;
+        // block align and bits per sample
+        writer.write_all(&2_u16.to_le_bytes())?;
+        writer.write_all(&16_u16.to_le_bytes())?;
+        writer.write_all(b"data")?;
+        writer.write_all(&data_length.to_le_bytes())?;
+        for sample in samples {
+            #[allow(clippy::cast_possible_truncation)]
+            let sample = (sample * f64::from(i16::MAX)).round() as i16;
+            writer.write_all(&sample.to_le_bytes())?;
+        }
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn waveforms() {
+        for waveform in [Waveform::Sine, Waveform::Sawtooth, Waveform::Square] {
+            assert!(waveform.sample(0.0, 440.0, 44100.0).abs() < 1e-9);
+        }
+        assert!((Waveform::Sine.sample(0.25, 440.0, 44100.0) - 1.0).abs() < 1e-9);
+        assert!((Waveform::Sawtooth.sample(0.25, 440.0, 44100.0) - 0.5).abs() < 0.01);
+        assert!((Waveform::Square.sample(0.25, 440.0, 44100.0) - 1.0).abs() < 0.05);
+        // no partials above the nyquist frequency
+        assert!((Waveform::Square.sample(0.25, 16000.0, 44100.0) - 4.0 / PI).abs() < 1e-9);
+    }
+
+    #[test]
+    fn render() {
+        let synth = Synth::default().with_sample_rate(1000).with_tempo(60.0);
+        assert!(synth.render(&[]).is_empty());
+
+        let note = Timed::new(Pitch::from_str("A4").unwrap(), 1.0, 1.0);
+        let samples = synth.render(&[note]);
+        // one second of silence, one second of sound and the release
+        assert_eq!(samples.len(), 2100);
+        assert!(samples[..1000].iter().all(|s| *s == 0.0));
+        assert!(samples.iter().all(|s| s.abs() <= 1.0));
+        assert!(samples[1000..2000].iter().any(|s| s.abs() > 0.3));
+    }
+
+    #[test]
+    fn wav_header() {
+        let synth = Synth::default().with_sample_rate(8000);
+        let note = Timed::new(Pitch::from_str("C4").unwrap(), 0.0, 0.5);
+        let mut wav = Vec::new();
+        synth.write_wav(&[note], &mut wav).unwrap();
+        let samples = synth.render(&[note]).len();
+        assert_eq!(wav.len(), 44 + 2 * samples);
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(wav[24..28], 8000_u32.to_le_bytes());
+        assert_eq!(&wav[36..40], b"data");
+    }
+
+    #[test]
+    #[should_panic(expected = "tempo")]
+    fn zero_tempo() {
+        let _ = Synth::default().with_tempo(0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "tempo")]
+    fn infinite_tempo() {
+        let _ = Synth::default().with_tempo(f64::INFINITY);
+    }
+
+    #[test]
+    #[should_panic(expected = "A4")]
+    fn zero_tuning() {
+        let _ = Synth::default().with_tuning(0.0);
+    }
+
+    #[test]
+    fn low_notes() {
+        let synth = Synth::default()
+            .with_sample_rate(8000)
+            .with_waveform(Waveform::Sawtooth);
+        let note = Timed::new(Pitch::from_str("C-6").unwrap(), 0.0, 0.1);
+        assert!(synth.render(&[note]).iter().all(|s| s.is_finite()));
+        assert!(Waveform::Square.sample(0.25, 0.0, 44100.0).is_finite());
+    }
+
+    #[test]
+    #[should_panic(expected = "sample rate")]
+    fn zero_sample_rate() {
+        let _ = Synth::default().with_sample_rate(0);
+    }
+}
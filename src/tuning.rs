@@ -0,0 +1,511 @@
+//! This module contains tunings, which assign frequencies to pitches.
+//!
+//! Most tunings act on spelled [`Pitch`]es, so that enharmonically equivalent pitches like G# and
+//! Ab can be tuned differently.
+//! All tunings in this module are fixed by the frequency of A4, except for [`JustIntonation`]
+//! which is fixed by the frequency of its tonic.
+//! Scales and keyboard mappings from Scala files are available through [`ScalaTuning`].
+use crate::harmony::{ChromaticOctave, ChromaticPitch, Interval, Pitch, scale::Key};
+
+mod ratio;
+mod scala;
+pub use ratio::{HejiInterval, ParseRatioError, RatioInterval};
+pub use scala::{KeyboardMapping, ParseScalaError, ScalaPitch, ScalaScale, ScalaTuning};
+
+/// A tuning assigns a frequency to every pitch
+pub trait Tuning {
+    /// Returns the frequency of the pitch in Hz
+    fn frequency(&self, pitch: Pitch) -> f64;
+
+    /// Returns the frequency of the chromatic pitch in Hz
+    ///
+    /// The default implementation spells the pitch with [`ChromaticPitch::to_pitch`].
+    fn chromatic_frequency(&self, pitch: ChromaticPitch) -> f64 {
+        self.frequency(pitch.to_pitch())
+    }
+
+    /// Returns the chromatic pitch whose frequency is nearest to `frequency`,
+    /// together with the deviation of `frequency` from that pitch in cents
+    ///
+    /// Deviations which differ by less than a thousandth of a cent count as equal, and such ties
+    /// are resolved in favor of the lower pitch. So a frequency exactly between two pitches is
+    /// assigned to the lower pitch with a positive deviation, even after rounding errors.
+    /// Pitches whose frequency is not finite are skipped; if no pitch near `frequency` has a
+    /// finite frequency, the deviation is `NaN`.
+    /// If `frequency` is not finite and positive, the result is A4 with a deviation of `NaN`.
+    /// Only pitches within 4000 semitones of C4 are considered.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::{harmony::ChromaticPitch, tuning::{EqualTemperament, Tuning}};
+    /// let tuning = EqualTemperament::new(442.0);
+    /// let (pitch, cents) = tuning.nearest_chromatic(440.0);
+    /// assert_eq!(pitch, ChromaticPitch::new(9));
+    /// assert!((cents + 7.85).abs() < 0.01);
+    /// ```
+    fn nearest_chromatic(&self, frequency: f64) -> (ChromaticPitch, f64) {
+        // deviations which differ by less than this count as equal
+        const EPSILON: f64 = 1e-3;
+        // keeps the arithmetic of the tunings on pitches from overflowing
+        const MAX_STEPS: i16 = 4000;
+        if !frequency.is_finite() || frequency <= 0.0 {
+            return (a_4().to_chromatic(), f64::NAN);
+        }
+        let deviation = |steps: i16| {
+            let cents =
+                1200.0 * (frequency / self.chromatic_frequency(ChromaticPitch(steps))).log2();
+            Some(cents).filter(|c| c.is_finite())
+        };
+        let neighbors = |steps: i16| {
+            [-2, -1, 1, 2]
+                .into_iter()
+                .map(move |offset| steps + offset)
+                .filter(|steps| steps.abs() <= MAX_STEPS)
+                .filter_map(|steps| Some((steps, deviation(steps)?)))
+        };
+
+        // start at the nearest pitch in equal temperament and move to nearer pitches,
+        // looking past single pitches without a frequency
+        let reference = Some(self.chromatic_frequency(a_4().to_chromatic()))
+            .filter(|f| f.is_finite() && *f > 0.0)
+            .unwrap_or(440.0);
+        let max = f64::from(MAX_STEPS);
+        #[allow(clippy::cast_possible_truncation)]
+        let mut best = ((12.0 * (frequency / reference).log2())
+            .round()
+            .clamp(-max, max) as i16)
+            .saturating_add(9)
+            .min(MAX_STEPS);
+        let mut best_cents = deviation(best);
+        while let Some((steps, cents)) = neighbors(best)
+            .min_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+            .filter(|(_, cents)| best_cents.is_none_or(|b| cents.abs() < b.abs() - EPSILON))
+        {
+            (best, best_cents) = (steps, Some(cents));
+        }
+        let Some(cents) = best_cents else {
+            return (ChromaticPitch(best), f64::NAN);
+        };
+        // break ties in favor of the lower pitch
+        let (steps, cents) = neighbors(best)
+            .filter(|(steps, c)| *steps < best && c.abs() <= cents.abs() + EPSILON)
+            .min_by_key(|(steps, _)| *steps)
+            .unwrap_or((best, cents));
+        (ChromaticPitch(steps), cents)
+    }
+
+    /// Returns the pitch nearest to `frequency` spelled in `key`, together with the deviation of
+    /// `frequency` from that pitch in cents
+    ///
+    /// The nearest chromatic pitch is found with [`nearest_chromatic`][`Self::nearest_chromatic`]
+    /// and spelled with [`Key::spell`]. The deviation is measured from the spelled pitch, so in
+    /// tunings which distinguish enharmonic pitches it may differ from the chromatic deviation.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::{harmony::{scale::Key, Pitch, ParsePitchError}, tuning::{EqualTemperament, Tuning}};
+    /// # use std::str::FromStr;
+    /// let key = Key::major(Pitch::class_from_str("Eb")?);
+    /// let (pitch, cents) = EqualTemperament::default().nearest_pitch(420.0, &key);
+    /// assert_eq!(pitch, Pitch::from_str("Ab4")?);
+    /// assert!((cents - 19.46).abs() < 0.01);
+    /// # Ok::<(), ParsePitchError>(())
+    /// ```
+    fn nearest_pitch(&self, frequency: f64, key: &Key) -> (Pitch, f64) {
+        let pitch = key.spell(self.nearest_chromatic(frequency).0);
+        (pitch, 1200.0 * (frequency / self.frequency(pitch)).log2())
+    }
+}
+
+/// The frequency `cents` above `frequency`
+fn shift_by_cents(frequency: f64, cents: f64) -> f64 {
+    frequency * (cents / 1200.0).exp2()
+}
+
+/// The pitch A4, which fixes most tunings
+fn a_4() -> Pitch {
+    Pitch::new(5, 9)
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The usual twelve tone equal temperament
+///
+/// This is the tuning used by [`Pitch::to_frequency_tuning`].
+pub struct EqualTemperament {
+    a_4: f64,
+}
+
+impl EqualTemperament {
+    /// creates twelve tone equal temperament where A4 has frequency `a_4`
+    pub fn new(a_4: f64) -> Self {
+        Self { a_4 }
+    }
+}
+
+impl Default for EqualTemperament {
+    fn default() -> Self {
+        Self::new(440.0)
+    }
+}
+
+impl Tuning for EqualTemperament {
+    fn frequency(&self, pitch: Pitch) -> f64 {
+        self.chromatic_frequency(pitch.to_chromatic())
+    }
+
+    fn chromatic_frequency(&self, pitch: ChromaticPitch) -> f64 {
+        shift_by_cents(self.a_4, f64::from(pitch.to_num() - 9) * 100.0)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A regular temperament, a tuning generated by a single size of the perfect fifth and pure
+/// octaves.
+///
+/// Every interval is tuned by decomposing it into fifths and octaves,
+/// see [`Interval::to_fifths_and_octaves`].
+/// A fifth of 700 cents results in twelve tone equal temperament.
+///
+/// # Examples
+/// ```
+/// # use music_types::{harmony::{Pitch, ParsePitchError}, tuning::{RegularTemperament, Tuning}};
+/// # use std::str::FromStr;
+/// let pythagorean = RegularTemperament::pythagorean(440.0);
+/// let e_5 = pythagorean.frequency(Pitch::from_str("E5")?);
+/// assert!((e_5 - 660.0).abs() < 1e-9);
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub struct RegularTemperament {
+    fifth: f64,
+    a_4: f64,
+}
+
+impl RegularTemperament {
+    /// creates a regular temperament with a fifth of `fifth` cents where A4 has frequency `a_4`
+    pub fn new(fifth: f64, a_4: f64) -> Self {
+        Self { fifth, a_4 }
+    }
+
+    /// creates pythagorean tuning, the tuning with pure fifths of ratio 3/2
+    pub fn pythagorean(a_4: f64) -> Self {
+        Self::new(1200.0 * 1.5_f64.log2(), a_4)
+    }
+
+    /// creates quarter-comma meantone, the tuning with pure major thirds of ratio 5/4
+    pub fn quarter_comma_meantone(a_4: f64) -> Self {
+        Self::new(1200.0 * 5.0_f64.log2() / 4.0, a_4)
+    }
+
+    /// returns the size of the fifth in cents
+    pub fn fifth(&self) -> f64 {
+        self.fifth
+    }
+
+    /// returns the size of the interval in cents
+    pub fn cents(&self, interval: Interval) -> f64 {
+        let (fifths, octaves) = interval.to_fifths_and_octaves();
+        f64::from(fifths).mul_add(self.fifth, f64::from(octaves) * 1200.0)
+    }
+}
+
+impl Tuning for RegularTemperament {
+    fn frequency(&self, pitch: Pitch) -> f64 {
+        shift_by_cents(self.a_4, self.cents(pitch - a_4()))
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Five-limit just intonation relative to a tonic
+///
+/// Every pitch is tuned by a ratio built from the primes 2, 3 and 5 above the tonic.
+/// The pitches of the major scale get the usual ratios 9/8, 5/4, 4/3, 3/2, 5/3 and 15/8,
+/// the chromatic pitches in between get 16/15, 6/5, 45/32, 8/5 and 9/5, and pitches further out
+/// on the circle of fifths continue this pattern.
+///
+/// # Examples
+/// ```
+/// # use music_types::{harmony::{Pitch, ParsePitchError}, tuning::{JustIntonation, Tuning}};
+/// # use std::str::FromStr;
+/// let tuning = JustIntonation::new(Pitch::from_str("C4")?, 260.0);
+/// assert!((tuning.frequency(Pitch::from_str("E4")?) - 325.0).abs() < 1e-9);
+/// assert!((tuning.frequency(Pitch::from_str("A3")?) - 216.666).abs() < 1e-3);
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub struct JustIntonation {
+    tonic: Pitch,
+    tonic_frequency: f64,
+}
+
+impl JustIntonation {
+    /// creates just intonation relative to `tonic`, which has the frequency `tonic_frequency`
+    pub fn new(tonic: Pitch, tonic_frequency: f64) -> Self {
+        Self {
+            tonic,
+            tonic_frequency,
+        }
+    }
+
+    /// returns the frequency ratio of the interval
+    pub fn ratio(interval: Interval) -> f64 {
+        let (fifths, octaves) = interval.to_fifths_and_octaves();
+        // number of pure major thirds replacing four fifths each
+        let thirds = if fifths >= -1 {
+            (fifths + 1).div_euclid(4)
+        } else {
+            -(-fifths + 3).div_euclid(5)
+        };
+        let fifths = fifths - 4 * thirds;
+        // each group of four fifths of ratio 81/16 is replaced by a pure major seventeenth of
+        // ratio 5/1, which lowers it by a syntonic comma
+        1.5_f64.powi(fifths.into()) * 5.0_f64.powi(thirds.into()) * 2.0_f64.powi(octaves.into())
+    }
+}
+
+impl Tuning for JustIntonation {
+    fn frequency(&self, pitch: Pitch) -> f64 {
+        self.tonic_frequency * Self::ratio(pitch - self.tonic)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A well temperament, a tuning of the twelve chromatic pitch classes which repeats every octave.
+///
+/// Well temperaments do not distinguish enharmonically equivalent pitches.
+///
+/// # Examples
+/// ```
+/// # use music_types::{harmony::{Pitch, ParsePitchError}, tuning::{Tuning, WellTemperament}};
+/// # use std::str::FromStr;
+/// let tuning = WellTemperament::werckmeister_iii(440.0);
+/// assert!((tuning.frequency(Pitch::from_str("A4")?) - 440.0).abs() < 1e-9);
+/// assert_eq!(
+///     tuning.frequency(Pitch::from_str("G#4")?),
+///     tuning.frequency(Pitch::from_str("Ab4")?)
+/// );
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub struct WellTemperament {
+    cents: [f64; 12],
+    a_4: f64,
+}
+
+impl WellTemperament {
+    /// creates a well temperament from the size of the intervals from C to each chromatic pitch
+    /// class in cents, where A4 has frequency `a_4`
+    pub fn new(cents: [f64; 12], a_4: f64) -> Self {
+        Self { cents, a_4 }
+    }
+
+    /// creates Werckmeister III, which narrows the fifths C-G, G-D, D-A and B-F# by a quarter of
+    /// the pythagorean comma
+    pub fn werckmeister_iii(a_4: f64) -> Self {
+        Self::new(
+            [
+                0.0, 90.225, 192.18, 294.135, 390.225, 498.045, 588.27, 696.09, 792.18, 888.27,
+                996.09, 1092.18,
+            ],
+            a_4,
+        )
+    }
+
+    /// creates Vallotti's temperament, which narrows the six fifths from F to B by a sixth of the
+    /// pythagorean comma
+    pub fn vallotti(a_4: f64) -> Self {
+        Self::new(
+            [
+                0.0, 94.135, 196.09, 298.045, 392.18, 501.955, 592.18, 698.045, 796.09, 894.135,
+                1000.0, 1090.225,
+            ],
+            a_4,
+        )
+    }
+
+    fn cents_from_c_4(&self, pitch: ChromaticPitch) -> f64 {
+        let class = pitch % ChromaticOctave;
+        let octaves = (pitch.to_num() - class.to_num()) / 12;
+        let index = usize::try_from(class.to_num()).expect("the remainder is not negative");
+        self.cents[index] + f64::from(octaves) * 1200.0
+    }
+}
+
+impl Tuning for WellTemperament {
+    fn frequency(&self, pitch: Pitch) -> f64 {
+        self.chromatic_frequency(pitch.to_chromatic())
+    }
+
+    fn chromatic_frequency(&self, pitch: ChromaticPitch) -> f64 {
+        let a_4 = self.cents_from_c_4(a_4().to_chromatic());
+        shift_by_cents(self.a_4, self.cents_from_c_4(pitch) - a_4)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-6, "{a} is not close to {b}");
+    }
+
+    #[test]
+    fn equal_temperament() {
+        let tuning = EqualTemperament::default();
+        let regular = RegularTemperament::new(700.0, 440.0);
+        for s in ["C4", "Ab2", "G#2", "B#7", "Fb-1"] {
+            let pitch = Pitch::from_str(s).unwrap();
+            let single_precision = f64::from(pitch.to_frequency_tuning(440.0));
+            assert!((tuning.frequency(pitch) / single_precision - 1.0).abs() < 1e-6);
+            assert_close(tuning.frequency(pitch), regular.frequency(pitch));
+        }
+    }
+
+    #[test]
+    fn meantone() {
+        let tuning = RegularTemperament::quarter_comma_meantone(440.0);
+        let c = tuning.frequency(Pitch::from_str("C4").unwrap());
+        let e = tuning.frequency(Pitch::from_str("E4").unwrap());
+        assert_close(e / c, 1.25);
+        let g_sharp = tuning.frequency(Pitch::from_str("G#4").unwrap());
+        let a_flat = tuning.frequency(Pitch::from_str("Ab4").unwrap());
+        assert_close(1200.0 * (a_flat / g_sharp).log2(), 41.058_858);
+    }
+
+    #[test]
+    fn just_ratios() {
+        let ratios = [
+            ("1", 1.0),
+            ("m2", 16.0 / 15.0),
+            ("j2", 9.0 / 8.0),
+            ("m3", 6.0 / 5.0),
+            ("j3", 5.0 / 4.0),
+            ("4", 4.0 / 3.0),
+            ("a4", 45.0 / 32.0),
+            ("d5", 64.0 / 45.0),
+            ("5", 3.0 / 2.0),
+            ("m6", 8.0 / 5.0),
+            ("j6", 5.0 / 3.0),
+            ("m7", 9.0 / 5.0),
+            ("j7", 15.0 / 8.0),
+            ("8", 2.0),
+            ("a1", 25.0 / 24.0),
+            ("j10", 5.0 / 2.0),
+            ("-j3", 4.0 / 5.0),
+        ];
+        for (interval, ratio) in ratios {
+            assert_close(
+                JustIntonation::ratio(Interval::from_str(interval).unwrap()),
+                ratio,
+            );
+        }
+    }
+
+    #[test]
+    fn well_temperaments() {
+        for tuning in [
+            WellTemperament::werckmeister_iii(440.0),
+            WellTemperament::vallotti(440.0),
+        ] {
+            assert_close(tuning.frequency(Pitch::from_str("A4").unwrap()), 440.0);
+            assert_close(tuning.frequency(Pitch::from_str("A5").unwrap()), 880.0);
+            let c_4 = tuning.frequency(Pitch::from_str("C4").unwrap());
+            let c_3 = tuning.frequency(Pitch::from_str("C3").unwrap());
+            assert_close(c_4 / c_3, 2.0);
+        }
+    }
+
+    #[test]
+    fn vallotti_fifths() {
+        let tuning = WellTemperament::vallotti(440.0);
+        let mut pitch = Pitch::from_str("F3").unwrap();
+        for i in 0..12 {
+            let fifth = pitch + Interval::FIFTH;
+            let cents = 1200.0 * (tuning.frequency(fifth) / tuning.frequency(pitch)).log2();
+            let expected = if i < 6 { 698.045 } else { 701.955 };
+            assert!(
+                (cents - expected).abs() < 1e-3,
+                "fifth {i} has {cents} cents"
+            );
+            pitch = fifth;
+        }
+    }
+
+    #[test]
+    fn nearest() {
+        let tunings: [Box<dyn Tuning>; 3] = [
+            Box::new(EqualTemperament::default()),
+            Box::new(RegularTemperament::quarter_comma_meantone(440.0)),
+            Box::new(WellTemperament::werckmeister_iii(415.0)),
+        ];
+        for tuning in &tunings {
+            for steps in -40..40 {
+                let pitch = ChromaticPitch::new(steps);
+                let (nearest, cents) = tuning.nearest_chromatic(tuning.chromatic_frequency(pitch));
+                assert_eq!(nearest, pitch);
+                assert_close(cents, 0.0);
+            }
+        }
+
+        // quarter tones between C4 and C#4 belong to C4
+        let tuning = EqualTemperament::default();
+        let frequency = shift_by_cents(tuning.chromatic_frequency(ChromaticPitch::new(0)), 50.0);
+        let (nearest, cents) = tuning.nearest_chromatic(frequency);
+        assert_eq!(nearest, ChromaticPitch::new(0));
+        assert_close(cents, 50.0);
+        let (nearest, _) = tuning.nearest_chromatic(shift_by_cents(frequency, 0.01));
+        assert_eq!(nearest, ChromaticPitch::new(1));
+    }
+
+    #[test]
+    fn nearest_invalid() {
+        let tunings: [Box<dyn Tuning>; 3] = [
+            Box::new(EqualTemperament::default()),
+            Box::new(RegularTemperament::quarter_comma_meantone(440.0)),
+            Box::new(JustIntonation::new(Pitch::from_str("C4").unwrap(), 261.0)),
+        ];
+        for tuning in &tunings {
+            for frequency in [0.0, -440.0, f64::INFINITY, f64::NEG_INFINITY, f64::NAN] {
+                let (nearest, cents) = tuning.nearest_chromatic(frequency);
+                assert_eq!(nearest, ChromaticPitch::new(9));
+                assert!(cents.is_nan());
+            }
+            // extreme but valid frequencies do not overflow
+            for frequency in [f64::MIN_POSITIVE, 1e-300, 1e300, f64::MAX] {
+                let (nearest, _) = tuning.nearest_chromatic(frequency);
+                assert!(nearest.to_num().abs() <= 4000);
+            }
+        }
+    }
+
+    #[test]
+    fn nearest_scala() {
+        // a pentatonic scale with every key mapped far from twelve tone equal temperament
+        let tuning = ScalaTuning::new(ScalaScale::equal_division(5), KeyboardMapping::default());
+        let pitch = ChromaticPitch::new(30);
+        let (nearest, cents) = tuning.nearest_chromatic(tuning.chromatic_frequency(pitch));
+        assert_eq!(nearest, pitch);
+        assert_close(cents, 0.0);
+
+        // unmapped keys are skipped
+        let mapping = KeyboardMapping::new(60, 60, 261.0, 12, vec![Some(0), None, Some(2)]);
+        let tuning = ScalaTuning::new(ScalaScale::equal_division(12), mapping);
+        let (nearest, _) =
+            tuning.nearest_chromatic(tuning.chromatic_frequency(ChromaticPitch::new(0)) * 1.05);
+        assert_eq!(nearest, ChromaticPitch::new(0));
+    }
+
+    #[test]
+    fn nearest_spelled() {
+        let meantone = RegularTemperament::quarter_comma_meantone(440.0);
+        let key = Key::major(Pitch::class_from_str("E").unwrap());
+        let g_sharp = Pitch::from_str("G#4").unwrap();
+        let (pitch, cents) = meantone.nearest_pitch(meantone.frequency(g_sharp), &key);
+        assert_eq!(pitch, g_sharp);
+        assert_close(cents, 0.0);
+    }
+}
@@ -0,0 +1,511 @@
+use std::{
+    error::Error,
+    fmt,
+    ops::{Add, Neg, Sub},
+    str::FromStr,
+};
+
+use crate::harmony::Interval;
+
+/// The largest prime factor of a ratio whose monzo is computed
+const MAX_MONZO_PRIME: u64 = 1 << 16;
+
+/// The size of a pure fifth in cents
+const FIFTH_CENTS: f64 = 701.955_000_865_387_4;
+
+/// The primes which are notated by the Helmholtz-Ellis notation, together with the number of
+/// fifths of the pythagorean interval they are notated as and the direction of their comma.
+///
+/// For example 5/4 is notated as the major third 81/64, four fifths, lowered by a syntonic comma.
+const HEJI_PRIMES: [(u64, i16, i16); 9] = [
+    (5, 4, -1),
+    (7, -2, -1),
+    (11, -1, 1),
+    (13, -4, 1),
+    (17, 7, -1),
+    (19, -3, 1),
+    (23, 6, 1),
+    (29, -2, 1),
+    (31, 0, -1),
+];
+
+const fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// returns the prime factors of `n` with their multiplicity in ascending order
+fn factorize(mut n: u64) -> Vec<(u64, i32)> {
+    let mut factors = Vec::new();
+    let mut p = 2;
+    while p <= n / p {
+        let mut count = 0;
+        while n.is_multiple_of(p) {
+            n /= p;
+            count += 1;
+        }
+        if count > 0 {
+            factors.push((p, count));
+        }
+        p += 1;
+    }
+    if n > 1 {
+        factors.push((n, 1));
+    }
+    factors
+}
+
+fn is_prime(n: u64) -> bool {
+    factorize(n) == [(n, 1)]
+}
+
+/// returns the primes up to `limit` with the sieve of Eratosthenes
+fn primes_up_to(limit: usize) -> Vec<u64> {
+    let mut composite = vec![false; limit + 1];
+    let mut primes = Vec::new();
+    for n in 2..=limit {
+        if !composite[n] {
+            primes.push(n as u64);
+            for multiple in (n * n..=limit).step_by(n) {
+                composite[multiple] = true;
+            }
+        }
+    }
+    primes
+}
+
+/// returns the odd part of `n`
+const fn odd_part(n: u64) -> u64 {
+    n >> n.trailing_zeros()
+}
+
+#[derive(Debug)]
+/// Error that may occur when parsing a ratio.
+pub enum ParseRatioError {
+    /// The numerator or denominator is not a positive integer
+    InvalidNumber(String),
+}
+
+impl fmt::Display for ParseRatioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidNumber(s) => write!(f, "`{s}` is not a positive integer"),
+        }
+    }
+}
+
+impl Error for ParseRatioError {}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An interval given by a frequency ratio, as used in just intonation.
+///
+/// The ratio is always stored in lowest terms.
+/// Adding intervals multiplies their ratios, like adding [`Interval`]s stacks them.
+///
+/// # FromStr and Display implementation
+/// Ratios are written as `"numerator/denominator"`, a missing denominator is read as 1.
+/// ```
+/// # use music_types::tuning::{RatioInterval, ParseRatioError};
+/// # use std::str::FromStr;
+/// let fifth = RatioInterval::from_str("3/2")?;
+/// let fourth = RatioInterval::from_str("4/3")?;
+/// assert_eq!(fifth + fourth, RatioInterval::from_str("2")?);
+/// assert_eq!((fifth - fourth).to_string(), "9/8");
+/// # Ok::<(), ParseRatioError>(())
+/// ```
+pub struct RatioInterval {
+    numerator: u64,
+    denominator: u64,
+}
+
+impl RatioInterval {
+    /// the unison 1/1
+    pub const UNISON: Self = Self {
+        numerator: 1,
+        denominator: 1,
+    };
+
+    /// creates the interval `numerator/denominator`
+    ///
+    /// # Panics
+    /// Panics if the numerator or the denominator is zero.
+    pub fn new(numerator: u64, denominator: u64) -> Self {
+        assert!(
+            numerator != 0 && denominator != 0,
+            "the numerator and denominator of a ratio must not be zero"
+        );
+        let divisor = gcd(numerator, denominator);
+        Self {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        }
+    }
+
+    /// creates an interval from the exponents of the primes 2, 3, 5, 7, ...
+    ///
+    /// Returns `None` if the ratio does not fit into 64 bits.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::tuning::RatioInterval;
+    /// assert_eq!(RatioInterval::from_monzo(&[-2, 0, 1]), Some(RatioInterval::new(5, 4)));
+    /// assert_eq!(RatioInterval::from_monzo(&[-4, 4, -1]), Some(RatioInterval::new(81, 80)));
+    /// ```
+    pub fn from_monzo(monzo: &[i32]) -> Option<Self> {
+        let (mut numerator, mut denominator) = (1_u64, 1_u64);
+        let primes = (2_u64..).filter(|n| is_prime(*n));
+        for (&exponent, prime) in monzo.iter().zip(primes) {
+            let power = prime.checked_pow(exponent.unsigned_abs())?;
+            if exponent > 0 {
+                numerator = numerator.checked_mul(power)?;
+            } else {
+                denominator = denominator.checked_mul(power)?;
+            }
+        }
+        Some(Self::new(numerator, denominator))
+    }
+
+    /// returns the numerator
+    pub fn numerator(&self) -> u64 {
+        self.numerator
+    }
+
+    /// returns the denominator
+    pub fn denominator(&self) -> u64 {
+        self.denominator
+    }
+
+    /// returns the prime factors of the ratio in ascending order with their exponents, which are
+    /// negative for the factors of the denominator
+    fn factors(&self) -> Vec<(u64, i32)> {
+        let mut factors: Vec<_> = factorize(self.numerator)
+            .into_iter()
+            .chain(
+                factorize(self.denominator)
+                    .into_iter()
+                    .map(|(p, e)| (p, -e)),
+            )
+            .collect();
+        factors.sort_unstable();
+        factors
+    }
+
+    /// returns the exponent of `prime` in the ratio
+    fn exponent(&self, prime: u64) -> i32 {
+        self.factors()
+            .into_iter()
+            .find(|(p, _)| *p == prime)
+            .map_or(0, |(_, e)| e)
+    }
+
+    /// returns the exponents of the primes 2, 3, 5, 7, ... in the ratio, its coordinates in the
+    /// just intonation lattice
+    ///
+    /// The monzo ends with the largest prime factor, so it has an entry for every prime up to
+    /// it. Returns `None` if the largest prime factor is above 65536.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::tuning::RatioInterval;
+    /// assert_eq!(RatioInterval::new(5, 4).monzo(), Some(vec![-2, 0, 1]));
+    /// assert_eq!(RatioInterval::new(7, 6).monzo(), Some(vec![-1, -1, 0, 1]));
+    /// assert_eq!(RatioInterval::new(1_000_003, 1).monzo(), None);
+    /// ```
+    pub fn monzo(&self) -> Option<Vec<i32>> {
+        let factors = self.factors();
+        let largest = factors.last().map_or(1, |(p, _)| *p);
+        if largest > MAX_MONZO_PRIME {
+            return None;
+        }
+        let largest = usize::try_from(largest).ok()?;
+        Some(
+            primes_up_to(largest)
+                .into_iter()
+                .map(|p| factors.iter().find(|(q, _)| *q == p).map_or(0, |(_, e)| *e))
+                .collect(),
+        )
+    }
+
+    /// returns the size of the interval in cents
+    #[allow(clippy::cast_precision_loss)]
+    pub fn cents(&self) -> f64 {
+        1200.0 * (self.numerator as f64 / self.denominator as f64).log2()
+    }
+
+    /// returns the Tenney height `log2(numerator * denominator)`, a measure of the complexity
+    /// of the ratio
+    #[allow(clippy::cast_precision_loss)]
+    pub fn tenney_height(&self) -> f64 {
+        (self.numerator as f64).log2() + (self.denominator as f64).log2()
+    }
+
+    /// returns the odd limit, the larger of the odd parts of the numerator and the denominator
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::tuning::RatioInterval;
+    /// assert_eq!(RatioInterval::new(3, 2).odd_limit(), 3);
+    /// assert_eq!(RatioInterval::new(15, 8).odd_limit(), 15);
+    /// assert_eq!(RatioInterval::new(7, 5).odd_limit(), 7);
+    /// ```
+    pub fn odd_limit(&self) -> u64 {
+        odd_part(self.numerator).max(odd_part(self.denominator))
+    }
+
+    /// returns the prime limit, the largest prime factor of the ratio
+    ///
+    /// The prime limit of the unison is 1.
+    pub fn prime_limit(&self) -> u64 {
+        self.factors().last().map_or(1, |(p, _)| *p)
+    }
+
+    /// returns the spelling of the interval in the Helmholtz-Ellis notation
+    ///
+    /// Returns `None` if the ratio has a prime factor above 31, which has no HEJI accidental.
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::tuning::RatioInterval;
+    /// assert_eq!(RatioInterval::new(5, 4).to_heji().unwrap().to_string(), "j3-5");
+    /// assert_eq!(RatioInterval::new(7, 4).to_heji().unwrap().to_string(), "m7-7");
+    /// assert_eq!(RatioInterval::new(11, 8).to_heji().unwrap().to_string(), "4+11");
+    /// assert_eq!(RatioInterval::new(81, 80).to_heji().unwrap().to_string(), "1+5");
+    /// assert_eq!(RatioInterval::new(37, 32).to_heji(), None);
+    /// ```
+    pub fn to_heji(&self) -> Option<HejiInterval> {
+        if self.prime_limit() > 31 {
+            return None;
+        }
+        let mut fifths = i16::try_from(self.exponent(3)).ok()?;
+        let mut commas = Vec::new();
+        for (prime, prime_fifths, direction) in HEJI_PRIMES {
+            let exponent = i16::try_from(self.exponent(prime)).ok()?;
+            if exponent != 0 {
+                fifths += exponent * prime_fifths;
+                commas.push((prime, exponent * direction));
+            }
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        let octaves = ((self.cents() - f64::from(fifths) * FIFTH_CENTS) / 1200.0).round() as i16;
+        let interval = Interval::new(7 * fifths + 12 * octaves, 4 * fifths + 7 * octaves);
+        Some(HejiInterval { interval, commas })
+    }
+
+    /// returns the spelled interval which the ratio is notated as in the Helmholtz-Ellis notation
+    ///
+    /// See [`to_heji`][`Self::to_heji`].
+    pub fn to_interval(&self) -> Option<Interval> {
+        self.to_heji().map(|heji| heji.interval)
+    }
+
+    /// stacks the intervals, returning `None` if the resulting ratio does not fit into 64 bits
+    ///
+    /// # Examples
+    /// ```
+    /// # use music_types::tuning::RatioInterval;
+    /// let fifth = RatioInterval::new(3, 2);
+    /// assert_eq!(fifth.checked_add(fifth), Some(RatioInterval::new(9, 4)));
+    /// let large = RatioInterval::new(3_u64.pow(40), 1);
+    /// assert_eq!(large.checked_add(large), None);
+    /// ```
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        // cancel before multiplying to avoid overflows
+        let a = gcd(self.numerator, rhs.denominator);
+        let b = gcd(rhs.numerator, self.denominator);
+        Some(Self::new(
+            (self.numerator / a).checked_mul(rhs.numerator / b)?,
+            (self.denominator / b).checked_mul(rhs.denominator / a)?,
+        ))
+    }
+
+    /// subtracts the intervals, returning `None` if the resulting ratio does not fit into 64 bits
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(-rhs)
+    }
+}
+
+impl Default for RatioInterval {
+    fn default() -> Self {
+        Self::UNISON
+    }
+}
+
+impl Add for RatioInterval {
+    type Output = Self;
+
+    /// stacks the intervals by multiplying their ratios
+    ///
+    /// # Panics
+    /// Panics if the resulting ratio does not fit into 64 bits, see
+    /// [`checked_add`][`RatioInterval::checked_add`].
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs)
+            .expect("the ratio of the sum of the intervals overflows")
+    }
+}
+
+impl Neg for RatioInterval {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            numerator: self.denominator,
+            denominator: self.numerator,
+        }
+    }
+}
+
+impl Sub for RatioInterval {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+impl fmt::Display for RatioInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+impl FromStr for RatioInterval {
+    type Err = ParseRatioError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (numerator, denominator) = s.split_once('/').unwrap_or((s, "1"));
+        let parse = |n: &str| match n.trim().parse() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(ParseRatioError::InvalidNumber(n.to_string())),
+        };
+        Ok(Self::new(parse(numerator)?, parse(denominator)?))
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A just interval spelled in the Helmholtz-Ellis notation (HEJI): a pythagorean interval
+/// altered by prime commas.
+///
+/// # Display implementation
+/// The spelled interval is followed by `"+p"` or `"-p"` for every comma of the prime `p` which
+/// raises or lowers the interval, e.g. `"j3-5"` for 5/4.
+pub struct HejiInterval {
+    interval: Interval,
+    commas: Vec<(u64, i16)>,
+}
+
+impl HejiInterval {
+    /// returns the pythagorean interval
+    pub fn interval(&self) -> Interval {
+        self.interval
+    }
+
+    /// returns the primes with the number of commas by which they raise the interval
+    pub fn commas(&self) -> &[(u64, i16)] {
+        &self.commas
+    }
+}
+
+impl fmt::Display for HejiInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.interval)?;
+        for (prime, count) in &self.commas {
+            let sign = if *count > 0 { '+' } else { '-' };
+            for _ in 0..count.unsigned_abs() {
+                write!(f, "{sign}{prime}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn monzo() {
+        for (n, d) in [
+            (1, 1),
+            (3, 2),
+            (81, 80),
+            (7, 6),
+            (45, 32),
+            (31, 16),
+            (1, 37),
+        ] {
+            let ratio = RatioInterval::new(n, d);
+            assert_eq!(
+                RatioInterval::from_monzo(&ratio.monzo().unwrap()),
+                Some(ratio)
+            );
+        }
+        assert_eq!(
+            RatioInterval::new(65521, 1).monzo().map(|m| m.len()),
+            Some(6542)
+        );
+        assert_eq!(RatioInterval::new(1, 1_000_000_007).monzo(), None);
+        assert_eq!(RatioInterval::new(6, 4), RatioInterval::new(3, 2));
+        assert_eq!(RatioInterval::from_monzo(&[0, 100]), None);
+    }
+
+    #[test]
+    fn measures() {
+        let ratio = RatioInterval::new(5, 4);
+        assert!((ratio.cents() - 386.3137).abs() < 1e-4);
+        assert!((ratio.tenney_height() - 20_f64.log2()).abs() < 1e-9);
+        assert_eq!(RatioInterval::new(9, 8).prime_limit(), 3);
+        assert_eq!(RatioInterval::new(13, 12).prime_limit(), 13);
+        assert_eq!(RatioInterval::UNISON.prime_limit(), 1);
+        assert_eq!(RatioInterval::UNISON.odd_limit(), 1);
+    }
+
+    #[test]
+    fn spelling() {
+        let spell = |n, d| RatioInterval::new(n, d).to_heji().unwrap().to_string();
+        assert_eq!(spell(3, 2), "5");
+        assert_eq!(spell(2, 3), "-5");
+        assert_eq!(spell(6, 5), "m3+5");
+        assert_eq!(spell(25, 16), "a5-5-5");
+        assert_eq!(spell(13, 8), "m6+13");
+        assert_eq!(spell(5, 1), "j17-5");
+        assert_eq!(spell(7, 5), "d5+5-7");
+        // 17/16 is a pythagorean augmented unison lowered by 2187/2176
+        assert_eq!(spell(17, 16), "a1-17");
+        assert_eq!(spell(17, 9), "j7-17");
+        assert_eq!(spell(17, 14), "a2+7-17");
+        assert_eq!(
+            RatioInterval::new(10, 9).to_interval(),
+            Some(Interval::MAJ_SECOND)
+        );
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            RatioInterval::from_str("10/8").unwrap(),
+            RatioInterval::new(5, 4)
+        );
+        assert!(RatioInterval::from_str("0/1").is_err());
+        assert!(RatioInterval::from_str("3/-2").is_err());
+        assert!(RatioInterval::from_str("x").is_err());
+    }
+
+    #[test]
+    fn overflow() {
+        let large = RatioInterval::new(3_u64.pow(40), 1);
+        assert_eq!(large.checked_add(large), None);
+        assert_eq!(large.checked_sub(-large), None);
+        // common factors are canceled first
+        assert_eq!(large - large, RatioInterval::UNISON);
+        assert_eq!(
+            large.checked_add(RatioInterval::new(1, 3)),
+            Some(RatioInterval::new(3_u64.pow(39), 1))
+        );
+    }
+}
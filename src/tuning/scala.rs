@@ -0,0 +1,576 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use crate::{
+    div_remainder,
+    harmony::{ChromaticPitch, Pitch},
+    tuning::Tuning,
+};
+
+#[derive(Debug)]
+/// Error that may occur when parsing a Scala scale or keyboard mapping file.
+pub enum ParseScalaError {
+    /// The file ended before all required lines were read
+    UnexpectedEnd,
+    /// A line did not contain a valid number
+    InvalidNumber {
+        /// the line number, starting at 1
+        line: usize,
+        /// the text which could not be parsed
+        text: String,
+    },
+    /// A line did not contain a valid pitch, which is either cents containing a `.` or a ratio
+    InvalidPitch {
+        /// the line number, starting at 1
+        line: usize,
+        /// the text which could not be parsed
+        text: String,
+    },
+}
+
+impl fmt::Display for ParseScalaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseScalaError::UnexpectedEnd => write!(f, "unexpected end of file"),
+            ParseScalaError::InvalidNumber { line, text } => {
+                write!(f, "could not parse number `{text}` on line {line}")
+            }
+            ParseScalaError::InvalidPitch { line, text } => {
+                write!(f, "could not parse pitch `{text}` on line {line}")
+            }
+        }
+    }
+}
+
+impl Error for ParseScalaError {}
+
+/// Iterates over the lines of a Scala file which are not comments,
+/// together with their line numbers
+fn content_lines(s: &str) -> impl Iterator<Item = (usize, &str)> {
+    s.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.starts_with('!'))
+}
+
+fn parse_number<T: FromStr>(
+    lines: &mut impl Iterator<Item = (usize, impl AsRef<str>)>,
+) -> Result<T, ParseScalaError> {
+    let (line, text) = lines.next().ok_or(ParseScalaError::UnexpectedEnd)?;
+    let text = text.as_ref().split_whitespace().next().unwrap_or_default();
+    text.parse().map_err(|_| ParseScalaError::InvalidNumber {
+        line,
+        text: text.to_string(),
+    })
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A pitch of a Scala scale, measured from the first degree of the scale
+pub enum ScalaPitch {
+    /// A size in cents
+    Cents(f64),
+    /// A frequency ratio given by numerator and denominator
+    Ratio(u64, u64),
+}
+
+impl ScalaPitch {
+    /// returns the size of the pitch above the first degree in cents
+    pub fn cents(&self) -> f64 {
+        match *self {
+            Self::Cents(cents) => cents,
+            #[allow(clippy::cast_precision_loss)]
+            Self::Ratio(num, den) => 1200.0 * (num as f64 / den as f64).log2(),
+        }
+    }
+}
+
+impl fmt::Display for ScalaPitch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // cents are recognized by the decimal point
+            Self::Cents(cents) => write!(f, "{cents:.5}"),
+            Self::Ratio(num, den) => write!(f, "{num}/{den}"),
+        }
+    }
+}
+
+impl FromStr for ScalaPitch {
+    type Err = ParseScalaError;
+
+    /// Parses the pitch from a line of a scale file, where the line number is unknown
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseScalaError::InvalidPitch {
+            line: 0,
+            text: s.to_string(),
+        };
+        let s = s.split_whitespace().next().ok_or_else(err)?;
+        if s.contains('.') {
+            return s.parse().map(Self::Cents).map_err(|_| err());
+        }
+        let (num, den) = s.split_once('/').unwrap_or((s, "1"));
+        match (num.parse(), den.parse()) {
+            (Ok(num), Ok(den)) if num > 0 && den > 0 => Ok(Self::Ratio(num, den)),
+            _ => Err(err()),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A scale as stored in a Scala `.scl` file
+///
+/// The pitches are measured from the first degree of the scale, which is not stored.
+/// The last pitch is the period of the scale, usually the octave.
+///
+/// # FromStr and Display implementation
+/// ```
+/// # use music_types::tuning::{ScalaScale, ParseScalaError};
+/// # use std::str::FromStr;
+/// let file = "! meantone.scl
+/// !
+/// Quarter-comma meantone fifths
+///  2
+/// !
+///  696.57843
+///  2/1
+/// ";
+/// let scale = ScalaScale::from_str(file)?;
+/// assert_eq!(scale.description(), "Quarter-comma meantone fifths");
+/// assert_eq!(scale.len(), 2);
+/// assert_eq!(ScalaScale::from_str(&scale.to_string())?, scale);
+/// # Ok::<(), ParseScalaError>(())
+/// ```
+pub struct ScalaScale {
+    description: String,
+    pitches: Vec<ScalaPitch>,
+}
+
+impl ScalaScale {
+    /// creates a scale from its description and pitches
+    pub fn new(description: String, pitches: Vec<ScalaPitch>) -> Self {
+        Self {
+            description,
+            pitches,
+        }
+    }
+
+    /// creates the scale of `divisions` equal steps per octave
+    pub fn equal_division(divisions: u16) -> Self {
+        let step = 1200.0 / f64::from(divisions);
+        let mut pitches: Vec<_> = (1..divisions)
+            .map(|i| ScalaPitch::Cents(f64::from(i) * step))
+            .collect();
+        pitches.push(ScalaPitch::Ratio(2, 1));
+        Self::new(
+            format!("{divisions} equal divisions of the octave"),
+            pitches,
+        )
+    }
+
+    /// returns the description of the scale
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// returns the pitches of the scale, excluding the first degree
+    pub fn pitches(&self) -> &[ScalaPitch] {
+        &self.pitches
+    }
+
+    /// returns the number of degrees in one period of the scale
+    pub fn len(&self) -> usize {
+        self.pitches.len()
+    }
+
+    /// returns true if the scale has no pitches
+    pub fn is_empty(&self) -> bool {
+        self.pitches.is_empty()
+    }
+
+    /// returns the size of the period in cents
+    pub fn period(&self) -> f64 {
+        self.pitches.last().map_or(1200.0, ScalaPitch::cents)
+    }
+
+    /// returns the size of the scale degree `degree` above the first degree in cents
+    ///
+    /// Degrees outside of the first period are repeated at the period.
+    pub fn cents(&self, degree: i16) -> f64 {
+        let Ok(len) = i16::try_from(self.len()) else {
+            return f64::NAN;
+        };
+        if len == 0 {
+            return 0.0;
+        }
+        let (periods, degree) = div_remainder(degree, len);
+        let cents = usize::try_from(degree - 1)
+            .ok()
+            .map_or(0.0, |i| self.pitches[i].cents());
+        f64::from(periods).mul_add(self.period(), cents)
+    }
+}
+
+impl fmt::Display for ScalaScale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "!")?;
+        writeln!(f, "{}", self.description)?;
+        writeln!(f, " {}", self.pitches.len())?;
+        writeln!(f, "!")?;
+        for pitch in &self.pitches {
+            writeln!(f, " {pitch}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ScalaScale {
+    type Err = ParseScalaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = content_lines(s);
+        let (_, description) = lines.next().ok_or(ParseScalaError::UnexpectedEnd)?;
+        let count: usize = parse_number(&mut lines)?;
+        let pitches = lines
+            .take(count)
+            .map(|(line, text)| {
+                ScalaPitch::from_str(text).map_err(|_| ParseScalaError::InvalidPitch {
+                    line,
+                    text: text.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if pitches.len() < count {
+            return Err(ParseScalaError::UnexpectedEnd);
+        }
+        Ok(Self::new(description.to_string(), pitches))
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A keyboard mapping as stored in a Scala `.kbm` file
+///
+/// A keyboard mapping assigns scale degrees to MIDI keys.
+/// The mapping pattern starts at the middle key and repeats every `mapping.len()` keys, moving
+/// up by the formal octave each time. An empty pattern maps consecutive keys to consecutive scale
+/// degrees. Keys mapped to `None` are not tuned.
+///
+/// The default mapping is the empty pattern with middle key 60 and A4 (key 69) at 440 Hz.
+pub struct KeyboardMapping {
+    first_key: u8,
+    last_key: u8,
+    middle_key: u8,
+    reference_key: u8,
+    reference_frequency: f64,
+    octave_degree: i16,
+    mapping: Vec<Option<i16>>,
+}
+
+impl Default for KeyboardMapping {
+    fn default() -> Self {
+        Self::linear(60, 69, 440.0)
+    }
+}
+
+impl KeyboardMapping {
+    /// creates a mapping which maps `middle_key` to the first scale degree and consecutive keys
+    /// to consecutive scale degrees, tuning `reference_key` to `reference_frequency`
+    pub fn linear(middle_key: u8, reference_key: u8, reference_frequency: f64) -> Self {
+        Self {
+            first_key: 0,
+            last_key: 127,
+            middle_key,
+            reference_key,
+            reference_frequency,
+            octave_degree: 0,
+            mapping: Vec::new(),
+        }
+    }
+
+    /// creates a mapping from the mapping pattern and the scale degree reached after one
+    /// repetition of the pattern
+    pub fn new(
+        middle_key: u8,
+        reference_key: u8,
+        reference_frequency: f64,
+        octave_degree: i16,
+        mapping: Vec<Option<i16>>,
+    ) -> Self {
+        Self {
+            octave_degree,
+            mapping,
+            ..Self::linear(middle_key, reference_key, reference_frequency)
+        }
+    }
+
+    /// restricts the mapping to the keys in `first_key..=last_key`
+    pub fn with_key_range(mut self, first_key: u8, last_key: u8) -> Self {
+        self.first_key = first_key;
+        self.last_key = last_key;
+        self
+    }
+
+    /// returns the key which has the frequency returned by
+    /// [`reference_frequency`][`Self::reference_frequency`]
+    pub fn reference_key(&self) -> u8 {
+        self.reference_key
+    }
+
+    /// returns the frequency of the reference key
+    pub fn reference_frequency(&self) -> f64 {
+        self.reference_frequency
+    }
+
+    /// returns the scale degree of the key, or `None` if the key is not mapped
+    ///
+    /// For the empty pattern the scale degree counts the keys from the middle key.
+    pub fn degree(&self, key: i16) -> Option<i16> {
+        let (first, last) = (i16::from(self.first_key), i16::from(self.last_key));
+        if key < first || key > last {
+            return None;
+        }
+        let offset = key - i16::from(self.middle_key);
+        let Ok(size) = i16::try_from(self.mapping.len()) else {
+            return None;
+        };
+        if size == 0 {
+            return Some(offset);
+        }
+        let (octaves, index) = div_remainder(offset, size);
+        let degree = (*self.mapping.get(usize::try_from(index).ok()?)?)?;
+        Some(octaves * self.octave_degree + degree)
+    }
+}
+
+impl fmt::Display for KeyboardMapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "! size of map")?;
+        writeln!(f, "{}", self.mapping.len())?;
+        writeln!(f, "! first and last MIDI note to retune")?;
+        writeln!(f, "{}", self.first_key)?;
+        writeln!(f, "{}", self.last_key)?;
+        writeln!(f, "! middle note")?;
+        writeln!(f, "{}", self.middle_key)?;
+        writeln!(f, "! reference note and frequency")?;
+        writeln!(f, "{}", self.reference_key)?;
+        writeln!(f, "{:.6}", self.reference_frequency)?;
+        writeln!(f, "! formal octave degree")?;
+        writeln!(f, "{}", self.octave_degree)?;
+        writeln!(f, "! mapping")?;
+        for degree in &self.mapping {
+            match degree {
+                Some(degree) => writeln!(f, "{degree}")?,
+                None => writeln!(f, "x")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for KeyboardMapping {
+    type Err = ParseScalaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = content_lines(s).filter(|(_, line)| !line.is_empty());
+        let size: usize = parse_number(&mut lines)?;
+        let first_key = parse_number(&mut lines)?;
+        let last_key = parse_number(&mut lines)?;
+        let middle_key = parse_number(&mut lines)?;
+        let reference_key = parse_number(&mut lines)?;
+        let reference_frequency = parse_number(&mut lines)?;
+        let octave_degree = parse_number(&mut lines)?;
+        let mut mapping = Vec::with_capacity(size);
+        for _ in 0..size {
+            let Some((line, text)) = lines.next() else {
+                // missing entries at the end of the mapping are unmapped
+                mapping.push(None);
+                continue;
+            };
+            if text.starts_with('x') {
+                mapping.push(None);
+            } else {
+                mapping.push(Some(parse_number(&mut std::iter::once((line, text)))?));
+            }
+        }
+        Ok(Self {
+            first_key,
+            last_key,
+            middle_key,
+            reference_key,
+            reference_frequency,
+            octave_degree,
+            mapping,
+        })
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A tuning given by a Scala scale and a keyboard mapping
+///
+/// Pitches are mapped to MIDI keys by their chromatic pitch, so the spelling of a pitch is
+/// ignored. Keys which are not mapped by the keyboard mapping have the frequency `NaN`.
+///
+/// # Examples
+/// ```
+/// # use music_types::{harmony::{ChromaticPitch, Pitch, ParsePitchError}, tuning::{ScalaScale, ScalaTuning}};
+/// # use std::str::FromStr;
+/// let tuning = ScalaTuning::new(ScalaScale::equal_division(12), Default::default());
+/// let frequency = Pitch::from_str("A5")?.to_frequency_with(&tuning);
+/// assert!((frequency - 880.0).abs() < 1e-9);
+/// # Ok::<(), ParsePitchError>(())
+/// ```
+pub struct ScalaTuning {
+    scale: ScalaScale,
+    mapping: KeyboardMapping,
+}
+
+impl ScalaTuning {
+    /// creates the tuning from a scale and a keyboard mapping
+    pub fn new(scale: ScalaScale, mapping: KeyboardMapping) -> Self {
+        Self { scale, mapping }
+    }
+
+    /// returns the scale
+    pub fn scale(&self) -> &ScalaScale {
+        &self.scale
+    }
+
+    /// returns the keyboard mapping
+    pub fn mapping(&self) -> &KeyboardMapping {
+        &self.mapping
+    }
+
+    /// returns the size of the interval from the reference key to `key` in cents,
+    /// or `None` if either key is not mapped
+    pub fn key_cents(&self, key: i16) -> Option<f64> {
+        let reference = self.mapping.degree(i16::from(self.mapping.reference_key))?;
+        let degree = self.mapping.degree(key)?;
+        Some(self.scale.cents(degree) - self.scale.cents(reference))
+    }
+
+    /// returns the frequency of `key`, or `None` if the key is not mapped
+    pub fn key_frequency(&self, key: i16) -> Option<f64> {
+        self.key_cents(key)
+            .map(|cents| self.mapping.reference_frequency * (cents / 1200.0).exp2())
+    }
+
+    /// returns the frequency of a MIDI key, or `None` if the key is not mapped
+    pub fn midi_frequency(&self, key: u8) -> Option<f64> {
+        self.key_frequency(key.into())
+    }
+}
+
+impl Tuning for ScalaTuning {
+    fn frequency(&self, pitch: Pitch) -> f64 {
+        self.chromatic_frequency(pitch.to_chromatic())
+    }
+
+    fn chromatic_frequency(&self, pitch: ChromaticPitch) -> f64 {
+        // MIDI key of C4
+        let key = pitch.to_num() + 60;
+        self.key_frequency(key).unwrap_or(f64::NAN)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PYTHAGOREAN: &str = "! pyth_12.scl
+!
+12-tone Pythagorean scale
+12
+!
+2187/2048
+9/8
+32/27
+81/64
+4/3
+729/512
+3/2
+6561/4096
+27/16
+16/9
+243/128
+2/1
+";
+
+    const WHITE_KEYS: &str = "! white keys only, tuning C4 to 261.6 Hz
+12
+0
+127
+60
+60
+261.6
+7
+0
+x
+1
+x
+2
+3
+x
+4
+x
+5
+x
+6
+";
+
+    #[test]
+    fn parse_scale() {
+        let scale = ScalaScale::from_str(PYTHAGOREAN).unwrap();
+        assert_eq!(scale.description(), "12-tone Pythagorean scale");
+        assert_eq!(scale.len(), 12);
+        assert_eq!(scale.pitches()[6], ScalaPitch::Ratio(3, 2));
+        assert!((scale.cents(7) - 701.955).abs() < 1e-3);
+        assert!((scale.cents(19) - 1901.955).abs() < 1e-3);
+        assert!((scale.cents(-5) + 498.045).abs() < 1e-3);
+        assert_eq!(ScalaScale::from_str(&scale.to_string()).unwrap(), scale);
+    }
+
+    #[test]
+    fn parse_scale_fail() {
+        assert!(matches!(
+            ScalaScale::from_str("description\n 2\n 3/2\n"),
+            Err(ParseScalaError::UnexpectedEnd)
+        ));
+        assert!(matches!(
+            ScalaScale::from_str("description\n 1\n -3/2\n"),
+            Err(ParseScalaError::InvalidPitch { line: 3, .. })
+        ));
+        assert!(matches!(
+            ScalaScale::from_str("description\n two\n"),
+            Err(ParseScalaError::InvalidNumber { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn white_keys() {
+        let mapping = KeyboardMapping::from_str(WHITE_KEYS).unwrap();
+        assert_eq!(
+            KeyboardMapping::from_str(&mapping.to_string()).unwrap(),
+            mapping
+        );
+        let scale =
+            ScalaScale::from_str("7 notes\n7\n9/8\n5/4\n4/3\n3/2\n5/3\n15/8\n2/1\n").unwrap();
+        let tuning = ScalaTuning::new(scale, mapping);
+        assert_eq!(tuning.midi_frequency(60), Some(261.6));
+        assert!(tuning.midi_frequency(61).is_none());
+        assert!((tuning.midi_frequency(64).unwrap() - 327.0).abs() < 1e-9);
+        assert!((tuning.midi_frequency(72).unwrap() - 523.2).abs() < 1e-9);
+        assert!((tuning.midi_frequency(57).unwrap() - 261.6 * 5.0 / 6.0).abs() < 1e-9);
+        assert!(tuning.chromatic_frequency(ChromaticPitch::new(1)).is_nan());
+    }
+
+    #[test]
+    fn default_mapping() {
+        let tuning = ScalaTuning::new(ScalaScale::equal_division(12), KeyboardMapping::default());
+        for key in 0..=127 {
+            let pitch = ChromaticPitch::from_midi_pitch(key);
+            let expected = f64::from(pitch.to_frequency());
+            assert!((tuning.chromatic_frequency(pitch) / expected - 1.0).abs() < 1e-6);
+        }
+    }
+}